@@ -1,244 +1,130 @@
 // use crate::calculate_surface_area::*;
 use crate::helpers::*;
 use crate::pages::*;
-use ambience::Ambience;
+use alarm::AlarmState;
+use ambience::{Ambience, CoolingCapacity};
+use batch::BatchEntry;
+use calibration::Calibration;
 use chrono::DateTime;
 use chrono::{Duration, Local};
 use drink::*;
 use leptos::*;
-use timer_info::TimerInfo;
+use model::CoolingModelKind;
+use plan::PlannedDrink;
+use timer_info::{TimerInfo, TimerSnapshot};
 use timer_preset::TimerPreset;
 
 #[component]
 pub fn App() -> impl IntoView {
-    let drink_beer_5 = Drink::new(
-        "Bier",
-        "500ml Flasche",
-        "./assets/images/bier5.svg",
-        Container {
-            volume: milliliters_to_m3(500.0),
-            surface_area: 0.04064, // 0.04363705122848267 m^2
-            material: ContainerMaterial::Glass,
-            shape: ContainerShape::BeerBottle,
-        },
-        0.05,
-    );
-    let drink_beer_33 = Drink::new(
-        "Bier",
-        "330ml Flasche",
-        "./assets/images/bier5.svg",
-        Container {
-            volume: milliliters_to_m3(330.0),
-            surface_area: 0.03263,
-            material: ContainerMaterial::Glass,
-            shape: ContainerShape::BeerBottle,
-        },
-        0.05,
-    );
-    let drink_beer_5_can = Drink::new(
-        "Bier",
-        "500ml Dose",
-        "./assets/images/can5.svg",
-        Container {
-            volume: milliliters_to_m3(500.0),
-            surface_area: 0.03768, // 0.03825517374276292 m^2
-            material: ContainerMaterial::Aluminium,
-            shape: ContainerShape::Can,
-        },
-        0.05,
-    );
-    let drink_beer_33_can = Drink::new(
-        "Bier",
-        "330ml Dose",
-        "./assets/images/can33.svg",
-        Container {
-            volume: milliliters_to_m3(330.0),
-            surface_area: 0.02706,
-            material: ContainerMaterial::Aluminium,
-            shape: ContainerShape::Can,
-        },
-        0.05,
-    );
-    let drink_lemondade = Drink::new(
-        "Limonade",
-        "1L Flasche",
-        "./assets/images/coke.svg",
-        Container {
-            volume: milliliters_to_m3(1000.0),
-            surface_area: 0.06102,
-            material: ContainerMaterial::Plastic,
-            shape: ContainerShape::PetBottle,
-        },
-        0.00,
-    );
-    let drink_wine = Drink::new(
-        "Rotwein",
-        "750ml Flasche",
-        "./assets/images/wein_rot.svg",
-        Container {
-            volume: milliliters_to_m3(750.0),
-            surface_area: 0.05138,
-            material: ContainerMaterial::Glass,
-            shape: ContainerShape::WineBottle,
-        },
-        0.15,
-    );
-    let drink_liquor = Drink::new(
-        "Schnaps",
-        "700ml Flasche",
-        "./assets/images/vodka.svg",
-        Container {
-            volume: milliliters_to_m3(700.0),
-            surface_area: 0.04844,
-            material: ContainerMaterial::Glass,
-            shape: ContainerShape::SchnapsBottle,
-        },
-        0.40,
-    );
+    let drink_beer_5 = catalog::beer_500_glass();
+    let drink_beer_33 = catalog::beer_330_glass();
+    let drink_beer_5_can = catalog::beer_500_can();
+    let drink_beer_33_can = catalog::beer_330_can();
+    let drink_lemondade = catalog::lemonade_1000_pet();
+    let drink_wine = catalog::red_wine_750_glass();
+    let drink_white_wine = catalog::white_wine_750_glass();
+    let drink_dessert_wine = catalog::dessert_wine_375_glass();
+    let drink_liquor = catalog::spirits_700_glass();
 
     // Initial Temperatures
-    let initial_kellerkalt = Ambience::new(
-        "Kellerkalt",
-        "./assets/images/ioicon/thermometer-outline.svg",
-        Temperature::new_with_unit(14.0, TemperatureUnit::DegCelsius),
-        None,
-    );
-    let initial_raumtemperatur = Ambience::new(
-        "Raumtemperatur",
-        "./assets/images/ioicon/partly-sunny-outline.svg",
-        Temperature::new_with_unit(20.0, TemperatureUnit::DegCelsius),
-        None,
-    );
-    let initial_sommertag = Ambience::new(
-        "Heißer Sommertag",
-        "./assets/images/ioicon/sunny-outline.svg",
-        Temperature::new_with_unit(30.0, TemperatureUnit::DegCelsius),
-        None,
-    );
+    let initial_kellerkalt = catalog::cellar();
+    let initial_raumtemperatur = catalog::room_temperature();
+    let initial_sommertag = catalog::summer_day();
 
     // Ambient Temperatures
-    let ambient_eisfach = Ambience::new(
-        "Eisfach",
-        "./assets/images/flake3.svg",
-        Temperature::new_with_unit(-18.0, TemperatureUnit::DegCelsius),
-        Some(Fluid::Air),
-    );
-    let ambient_eisbad = Ambience::new(
-        "Eisbad",
-        "./assets/images/flake.svg",
-        Temperature::new_with_unit(0.0, TemperatureUnit::DegCelsius),
-        Some(Fluid::Water),
-    );
-    let ambient_kuehlschrank = Ambience::new(
-        "Kühlschrank",
-        "./assets/images/ioicon/thermometer-outline.svg",
-        Temperature::new_with_unit(5.0, TemperatureUnit::DegCelsius),
-        Some(Fluid::Air),
-    );
+    let ambient_eisfach = catalog::freezer();
+    let ambient_eisbad = catalog::ice_bath();
+    let ambient_salz_eisbad = catalog::salted_ice_bath();
+    let ambient_kuehlschrank = catalog::fridge();
+    let ambient_eisfach_ventilator = catalog::fan_freezer();
+    let ambient_schnee = catalog::snow();
+    let ambient_trockeneis = catalog::dry_ice_bath();
 
     // Target Temperatures
-    let target_schnaps = Ambience::new(
-        "Optimal für Schnaps",
-        "./assets/images/vodka.svg",
-        Temperature::new_with_unit(2.0, TemperatureUnit::DegCelsius),
-        None,
+    let target_schnaps = catalog::target_schnaps();
+    let target_lemonade = catalog::target_lemonade();
+    let target_beer = catalog::target_beer();
+    let target_wine_white = catalog::target_white_wine();
+    let target_wine_red = catalog::target_red_wine();
+
+    // Timer Presets
+    let preset_beer = TimerPreset::new(
+        "Bier",
+        &target_beer.path_to_image,
+        drink_beer_5.clone(),
+        initial_raumtemperatur.clone(),
+        ambient_eisfach.clone(),
+        target_beer.clone(),
     );
-    let target_lemonade = Ambience::new(
-        "Optimal für Limonade",
-        "./assets/images/coke.svg",
-        Temperature::new_with_unit(4.0, TemperatureUnit::DegCelsius),
-        None,
+    let preset_wine_red = TimerPreset::new(
+        "Rotwein",
+        &target_wine_red.path_to_image,
+        drink_wine.clone(),
+        initial_raumtemperatur.clone(),
+        ambient_eisfach.clone(),
+        target_wine_red.clone(),
     );
-    let target_beer = Ambience::new(
-        "Optimal für Bier",
-        "./assets/images/bier5.svg",
-        Temperature::new_with_unit(6.0, TemperatureUnit::DegCelsius),
-        None,
+    // Red wine kept in the fridge warms up on the table
+    let preset_wine_red_warming = TimerPreset::new(
+        "Rotwein aus dem Kühlschrank",
+        &target_wine_red.path_to_image,
+        drink_wine.clone(),
+        ambient_kuehlschrank.clone(),
+        initial_raumtemperatur.clone(),
+        target_wine_red.clone(),
     );
-    let target_wine_white = Ambience::new(
-        "Optimal für Weißwein",
-        "./assets/images/wein_weiss.svg",
-        Temperature::new_with_unit(10.0, TemperatureUnit::DegCelsius),
-        None,
+    let preset_wine_white = TimerPreset::new(
+        "Weißwein",
+        &target_wine_white.path_to_image,
+        drink_white_wine.clone(),
+        initial_raumtemperatur.clone(),
+        ambient_eisfach.clone(),
+        target_wine_white.clone(),
     );
-    let target_wine_red = Ambience::new(
-        "Optimal für Rotwein",
-        "./assets/images/wein_rot.svg",
-        Temperature::new_with_unit(16.0, TemperatureUnit::DegCelsius),
-        None,
+    let preset_schnaps = TimerPreset::new(
+        "Schnaps",
+        &target_schnaps.path_to_image,
+        drink_liquor.clone(),
+        initial_raumtemperatur.clone(),
+        ambient_eisfach.clone(),
+        target_schnaps.clone(),
     );
-
-    // Timer Presets
-    let preset_beer = TimerPreset {
-        name: "Bier".to_string(),
-        path_to_image: target_beer.path_to_image.clone(),
-        drink: drink_beer_5.clone(),
-        initial_ambience: initial_raumtemperatur.clone(),
-        ambient_ambience: ambient_eisfach.clone(),
-        target_ambience: target_beer.clone(),
-    };
-    let preset_wine_red = TimerPreset {
-        name: "Rotwein".to_string(),
-        path_to_image: target_wine_red.path_to_image.clone(),
-        drink: drink_wine.clone(),
-        initial_ambience: initial_raumtemperatur.clone(),
-        ambient_ambience: ambient_eisfach.clone(),
-        target_ambience: target_wine_red.clone(),
-    };
-    let preset_wine_white = TimerPreset {
-        name: "Weißwein".to_string(),
-        path_to_image: target_wine_white.path_to_image.clone(),
-        drink: drink_wine.clone(),
-        initial_ambience: initial_raumtemperatur.clone(),
-        ambient_ambience: ambient_eisfach.clone(),
-        target_ambience: target_wine_white.clone(),
-    };
-    let preset_schnaps = TimerPreset {
-        name: "Schnaps".to_string(),
-        path_to_image: target_schnaps.path_to_image.clone(),
-        drink: drink_liquor.clone(),
-        initial_ambience: initial_raumtemperatur.clone(),
-        ambient_ambience: ambient_eisfach.clone(),
-        target_ambience: target_schnaps.clone(),
-    };
-    let preset_beer_can = TimerPreset {
-        name: "Bier Dose 500".to_string(),
-        path_to_image: target_beer.path_to_image.clone(),
-        drink: drink_beer_5_can.clone(),
-        initial_ambience: initial_raumtemperatur.clone(),
-        ambient_ambience: ambient_eisfach.clone(),
-        target_ambience: target_beer.clone(),
-    };
-    let preset_dev = TimerPreset {
-        name: "Dev".to_string(),
-        path_to_image: target_beer.path_to_image.clone(),
-        drink: drink_beer_5_can.clone(),
-        initial_ambience: Ambience::new(
+    let preset_beer_can = TimerPreset::new(
+        "Bier Dose 500",
+        &target_beer.path_to_image,
+        drink_beer_5_can.clone(),
+        initial_raumtemperatur.clone(),
+        ambient_eisfach.clone(),
+        target_beer.clone(),
+    );
+    let preset_dev = TimerPreset::new(
+        "Dev",
+        &target_beer.path_to_image,
+        drink_beer_5_can.clone(),
+        Ambience::new(
             "dev1",
             "./assets/images/wein_rot.svg",
             Temperature::new_with_unit(11.0, TemperatureUnit::DegCelsius),
             None,
-        )
-        .clone(),
-        ambient_ambience: Ambience::new(
+        ),
+        Ambience::new(
             "dev2",
             "./assets/images/wein_rot.svg",
             Temperature::new_with_unit(-50.0, TemperatureUnit::DegCelsius),
             None,
-        )
-        .clone(),
-        target_ambience: Ambience::new(
+        ),
+        Ambience::new(
             "dev3",
             "./assets/images/wein_rot.svg",
             Temperature::new_with_unit(10.0, TemperatureUnit::DegCelsius),
             None,
-        )
-        .clone(),
-    };
+        ),
+    );
 
-    let timer_presets = vec![
+    let builtin_timer_presets = [
         preset_beer.clone(),
         preset_wine_red.clone(),
+        preset_wine_red_warming.clone(),
         preset_wine_white.clone(),
         preset_schnaps.clone(),
         preset_beer_can.clone(),
@@ -246,6 +132,121 @@ pub fn App() -> impl IntoView {
     ];
     let selected_preset_signal = create_rw_signal(preset_beer);
 
+    // Everything the preset builder can combine
+    provide_context(BuiltinDrinks(vec![
+        drink_beer_5,
+        drink_beer_33,
+        drink_beer_5_can,
+        drink_beer_33_can,
+        drink_lemondade,
+        drink_wine,
+        drink_white_wine,
+        drink_dessert_wine,
+        drink_liquor,
+    ]));
+    let ambient_ambiences = vec![
+        ambient_eisfach.clone(),
+        ambient_eisbad,
+        ambient_salz_eisbad,
+        ambient_kuehlschrank.clone(),
+        ambient_eisfach_ventilator,
+        ambient_schnee,
+        ambient_trockeneis,
+        // Warming drinks up
+        initial_raumtemperatur.clone(),
+        initial_sommertag.clone(),
+    ];
+    provide_context(KnownAmbiences {
+        initial: vec![
+            initial_kellerkalt,
+            initial_raumtemperatur.clone(),
+            initial_sommertag,
+            // Drinks that warm up, or thaw
+            ambient_kuehlschrank,
+            ambient_eisfach.clone(),
+        ],
+        ambient: ambient_ambiences.clone(),
+        target: vec![
+            target_schnaps,
+            target_lemonade,
+            target_beer,
+            target_wine_white,
+            target_wine_red,
+        ],
+        pause: initial_raumtemperatur.clone(),
+    });
+
+    // Drinks created by the user
+    let custom_drinks = create_rw_signal::<Vec<Drink>>(
+        storage::load(storage::CUSTOM_DRINKS_KEY).unwrap_or_default(),
+    );
+    create_effect(move |_| {
+        custom_drinks.with(|drinks| storage::save(storage::CUSTOM_DRINKS_KEY, drinks));
+    });
+    provide_context(CustomDrinks(custom_drinks));
+
+    // Presets created by the user
+    let custom_timer_presets = create_rw_signal::<Vec<TimerPreset>>(
+        storage::load(storage::CUSTOM_TIMER_PRESETS_KEY).unwrap_or_default(),
+    );
+    create_effect(move |_| {
+        custom_timer_presets
+            .with(|presets| storage::save(storage::CUSTOM_TIMER_PRESETS_KEY, presets));
+    });
+    provide_context(CustomTimerPresets(custom_timer_presets));
+
+    let timer_presets = Signal::derive(move || {
+        let custom_drink_timer_presets = custom_drinks.get().into_iter().map(|drink| {
+            // Ideal serving temperature of its type and style
+            let target_ambience = drink.serving_target();
+            TimerPreset::new(
+                &drink.name.clone(),
+                &drink.path_to_image.clone(),
+                drink,
+                initial_raumtemperatur.clone(),
+                ambient_eisfach.clone(),
+                target_ambience,
+            )
+        });
+
+        builtin_timer_presets
+            .iter()
+            .cloned()
+            .chain(custom_drink_timer_presets)
+            .collect::<Vec<_>>()
+    });
+
+    // Cooling coefficients fitted to the user's measurements
+    let calibrations = create_rw_signal::<Vec<Calibration>>(
+        storage::load(storage::CALIBRATIONS_KEY).unwrap_or_default(),
+    );
+    create_effect(move |_| {
+        calibrations.with(|calibrations| storage::save(storage::CALIBRATIONS_KEY, calibrations));
+    });
+    provide_context(Calibrations(calibrations));
+
+    // Temperature unit, US browsers default to Fahrenheit
+    let unit_preference = create_rw_signal(
+        storage::load::<TemperatureUnit>(storage::UNIT_PREFERENCE_KEY).unwrap_or_else(|| {
+            match window().navigator().language().as_deref() {
+                Some("en-US") => TemperatureUnit::DegFahrenheit,
+                _ => TemperatureUnit::DegCelsius,
+            }
+        }),
+    );
+    create_effect(move |_| {
+        storage::save(storage::UNIT_PREFERENCE_KEY, &unit_preference.get());
+    });
+    provide_context(UnitPreference(unit_preference));
+
+    let model_preference = create_rw_signal(
+        storage::load::<CoolingModelKind>(storage::MODEL_PREFERENCE_KEY).unwrap_or_default(),
+    );
+    create_effect(move |_| {
+        storage::save(storage::MODEL_PREFERENCE_KEY, &model_preference.get());
+    });
+    provide_context(ModelPreference(model_preference));
+
     // leptos::logging::log!("{:#?}", drinks);
 
     // Current Time
@@ -260,11 +261,200 @@ pub fn App() -> impl IntoView {
     );
     provide_context(CurrentTime(current_time_signal));
 
-    // Running timers
-    let currently_running_timers = create_rw_signal::<Vec<TimerInfo>>(vec![]);
+    // Running timers, restored from the last session
+    let currently_running_timers = create_rw_signal::<Vec<TimerInfo>>(
+        storage::load::<Vec<TimerSnapshot>>(storage::RUNNING_TIMERS_KEY)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|snapshot| TimerInfo::from_snapshot(snapshot, current_time_signal))
+            .collect(),
+    );
+    create_effect(move |_| {
+        let snapshots = currently_running_timers
+            .with(|timers| timers.iter().map(TimerInfo::snapshot).collect::<Vec<_>>());
+        storage::save(storage::RUNNING_TIMERS_KEY, &snapshots);
+    });
     provide_context(CurrentlyRunningTimers(currently_running_timers));
 
-    view! { <Home timer_presets=timer_presets.clone() selected_preset_signal/> }
+    // Drinks cooling in the same appliance warm it up and slow each other down
+    let known_ambient_ambiences = ambient_ambiences.clone();
+    create_effect(move |_| {
+        currently_running_timers.with(|timers| {
+            let mut groups: Vec<(Ambience, CoolingCapacity, Vec<&TimerInfo>)> = vec![];
+            for timer in timers {
+                let ambience = timer.current_ambience.get();
+                // Timers saved before capacities existed lack them
+                let capacity = ambience.capacity.or_else(|| {
+                    known_ambient_ambiences
+                        .iter()
+                        .find(|known| known.id == ambience.id)
+                        .and_then(|known| known.capacity)
+                });
+                let (Some(capacity), false) = (capacity, timer.paused.get()) else {
+                    timer.batch.set(None);
+                    continue;
+                };
+                match groups
+                    .iter_mut()
+                    .find(|(known, ..)| known.id == ambience.id)
+                {
+                    Some((.., group)) => group.push(timer),
+                    None => groups.push((ambience, capacity, vec![timer])),
+                }
+            }
+
+            for (ambience, capacity, group) in groups {
+                // A single drink is what the exponential model is made for
+                if group.len() < 2 {
+                    group.iter().for_each(|timer| timer.batch.set(None));
+                    continue;
+                }
+
+                let predictions = group
+                    .iter()
+                    .map(|timer| {
+                        Ok(BatchEntry {
+                            id: timer.id,
+                            drink: &timer.drink,
+                            entered: timer
+                                .segments
+                                .with(|segments| segments.last().map(|s| s.timestamp_started))
+                                .unwrap_or(timer.timestamp_started),
+                            start_temperature: timer.segments.with(|segments| {
+                                segment::current_start_temperature(
+                                    segments,
+                                    timer.initial_ambience.temperature,
+                                    &timer.drink,
+                                    &timer.model,
+                                )
+                            })?,
+                            target_temperature: timer.target_ambience.temperature,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, CoolingError>>()
+                    .and_then(|entries| batch::simulate(&ambience, capacity, &entries));
+                match predictions {
+                    Ok(predictions) => {
+                        for (timer, prediction) in group.iter().zip(predictions) {
+                            timer.batch.set(Some(prediction));
+                        }
+                    }
+                    // The timers show the error themselves
+                    Err(_) => group.iter().for_each(|timer| timer.batch.set(None)),
+                }
+            }
+        });
+    });
+
+    // Alarms for finished and snoozed timers
+    create_effect(move |_| {
+        let now = current_time_signal.get();
+        currently_running_timers.with(|timers| {
+            for timer in timers {
+                // Moving a drink back out of the target range re-arms the alarm
+                if timer.alarm.get() != AlarmState::Pending && !timer.timer_finished.get() {
+                    timer.alarm.set(AlarmState::Pending);
+                    continue;
+                }
+                let ring = match timer.alarm.get() {
+                    AlarmState::Pending => timer.timer_finished.get(),
+                    AlarmState::Snoozed(until) => now >= until,
+                    AlarmState::Ringing | AlarmState::Dismissed => false,
+                };
+                if ring {
+                    timer.alarm.set(AlarmState::Ringing);
+                    alarm::ring(
+                        &match timer.is_warming() {
+                            true => format!("{} ist trinkbereit!", timer.drink.name),
+                            false => format!("{} ist kalt!", timer.drink.name),
+                        },
+                        &format!(
+                            "{} hat {} erreicht",
+                            timer.drink.description,
+                            timer
+                                .target_ambience
+                                .temperature
+                                .format(unit_preference.get_untracked(), true)
+                        ),
+                        &timer.id.to_string(),
+                    );
+                }
+            }
+        });
+    });
+
+    // Reminders to turn the bottle while the timer runs
+    create_effect(move |_| {
+        let now = current_time_signal.get();
+        currently_running_timers.with(|timers| {
+            for timer in timers {
+                let due = timer.rotation_reminders_due(now);
+                if due <= timer.rotation_reminders_sent.get() {
+                    continue;
+                }
+                // Reminders missed e.g. during a reload ring only once
+                timer.rotation_reminders_sent.set(due);
+                if timer.timer_finished.get() || timer.paused.get() {
+                    continue;
+                }
+                alarm::ring(
+                    &format!("{} drehen!", timer.drink.name),
+                    &format!(
+                        "Dreh {} kurz im {}",
+                        timer.drink.description,
+                        timer.current_ambience.get().name
+                    ),
+                    &format!("{}-rotation", timer.id),
+                );
+            }
+        });
+    });
+
+    // Drinks planned to be cold at a given time
+    let planned_drinks = create_rw_signal::<Vec<PlannedDrink>>(
+        storage::load(storage::PLANNED_DRINKS_KEY).unwrap_or_default(),
+    );
+    create_effect(move |_| {
+        planned_drinks.with(|plans| storage::save(storage::PLANNED_DRINKS_KEY, plans));
+    });
+    provide_context(PlannedDrinks(planned_drinks));
+
+    // "Put it in now" reminders for planned drinks
+    create_effect(move |_| {
+        let now = current_time_signal.get();
+        let due = planned_drinks.with(|plans| {
+            plans
+                .iter()
+                .filter(|plan| !plan.reminded && plan.is_due(now))
+                .map(|plan| plan.id)
+                .collect::<Vec<_>>()
+        });
+        if due.is_empty() {
+            return;
+        }
+
+        planned_drinks.update(|plans| {
+            for plan in plans.iter_mut().filter(|plan| due.contains(&plan.id)) {
+                plan.reminded = true;
+                let (action, ready) = match plan.preset.is_warming() {
+                    true => ("rausstellen", "trinkbereit"),
+                    false => ("reinlegen", "kalt"),
+                };
+                alarm::ring(
+                    &format!("{} jetzt {}!", plan.preset.drink.name, action),
+                    &format!(
+                        "{} soll um {} {} sein",
+                        plan.preset.drink.description,
+                        plan.ready_at.format("%H:%M"),
+                        ready
+                    ),
+                    &plan.id.to_string(),
+                );
+            }
+        });
+    });
+
+    view! { <Home timer_presets selected_preset_signal/> }
 }
 
 #[derive(Clone)]
@@ -272,3 +462,35 @@ pub struct CurrentTime(pub RwSignal<DateTime<Local>>);
 
 #[derive(Clone)]
 pub struct CurrentlyRunningTimers(pub RwSignal<Vec<TimerInfo>>);
+
+/// Temperature unit used for every display
+#[derive(Clone)]
+pub struct UnitPreference(pub RwSignal<TemperatureUnit>);
+
+/// Model used for the predictions of new timers
+#[derive(Clone)]
+pub struct ModelPreference(pub RwSignal<CoolingModelKind>);
+
+#[derive(Clone)]
+pub struct Calibrations(pub RwSignal<Vec<Calibration>>);
+
+#[derive(Clone)]
+pub struct CustomDrinks(pub RwSignal<Vec<Drink>>);
+
+#[derive(Clone)]
+pub struct BuiltinDrinks(pub Vec<Drink>);
+
+#[derive(Clone)]
+pub struct KnownAmbiences {
+    pub initial: Vec<Ambience>,
+    pub ambient: Vec<Ambience>,
+    pub target: Vec<Ambience>,
+    /// Where a paused drink waits
+    pub pause: Ambience,
+}
+
+#[derive(Clone)]
+pub struct CustomTimerPresets(pub RwSignal<Vec<TimerPreset>>);
+
+#[derive(Clone)]
+pub struct PlannedDrinks(pub RwSignal<Vec<PlannedDrink>>);
@@ -0,0 +1,422 @@
+use std::{
+    io::Write,
+    process::ExitCode,
+    thread,
+    time::{Duration as StdDuration, Instant},
+};
+
+use bier_timer::{
+    ambience::Agitation,
+    catalog,
+    drink::{Layer, LayerMaterial},
+    format_chrono_duration_precise, is_warming,
+    model::{CoolingModel, CoolingModelKind},
+    sink_temperature, target_reachable,
+    validity::{validity, ConfidenceBand},
+    Ambience, CoolingError, Drink, Phase, Temperature, TemperatureUnit,
+};
+use chrono::Duration;
+use clap::{Args, Parser, Subcommand};
+
+/// Predict how long drinks take to cool down or warm up
+#[derive(Parser)]
+#[command(name = "bier-timer", version)]
+struct Cli {
+    /// Print temperatures in degrees Fahrenheit
+    #[arg(long, global = true)]
+    fahrenheit: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Time until the drink reaches the target temperature
+    Predict {
+        #[command(flatten)]
+        setup: Setup,
+        /// Target temperature, e.g. `6C` or `beer`, defaults to the
+        /// ideal serving temperature of the drink
+        #[arg(long, value_parser = parse_ambience, allow_hyphen_values = true)]
+        to: Option<Ambience>,
+    },
+    /// Temperature of the drink over time, one line per step
+    Curve {
+        #[command(flatten)]
+        setup: Setup,
+        /// How long to follow the drink, in minutes
+        #[arg(long, default_value_t = 120)]
+        minutes: u32,
+        /// Minutes between two lines
+        #[arg(long, default_value_t = 5)]
+        step: u32,
+    },
+    /// Count down in the terminal until the drink is ready
+    Run {
+        #[command(flatten)]
+        setup: Setup,
+        /// Target temperature, e.g. `6C` or `beer`, defaults to the
+        /// ideal serving temperature of the drink
+        #[arg(long, value_parser = parse_ambience, allow_hyphen_values = true)]
+        to: Option<Ambience>,
+    },
+    /// Known drinks and ambiences
+    List,
+}
+
+#[derive(Args)]
+struct Setup {
+    /// Drink, see `bier-timer list`
+    #[arg(long, value_parser = parse_drink)]
+    drink: Drink,
+    /// Initial temperature, e.g. `20C`, `68F` or `room`
+    #[arg(long, value_parser = parse_ambience, allow_hyphen_values = true)]
+    from: Ambience,
+    /// Where the drink cools down or warms up, e.g. `freezer` or
+    /// `-18C` for air
+    #[arg(long = "in", value_parser = parse_ambience, allow_hyphen_values = true)]
+    ambience: Ambience,
+    /// How the fluid is moved: `still`, `stirred`, `rotated` or `fan`
+    #[arg(long, value_parser = parse_agitation, default_value = "still")]
+    agitation: Agitation,
+    /// Wrapped around the container, from the inside out: `label`,
+    /// `liner`, `koozie` or `wet-towel`, can be repeated
+    #[arg(long = "layer", value_parser = parse_layer)]
+    layers: Vec<LayerMaterial>,
+    /// Relative humidity of the air in percent, matters for drinks
+    /// wrapped in a wet towel
+    #[arg(long)]
+    humidity: Option<f64>,
+    /// How the temperature is predicted: `exponential` for a well
+    /// mixed drink or `fd` to let the core lag behind the wall
+    #[arg(long, value_parser = parse_model, default_value = "exponential")]
+    model: CoolingModelKind,
+}
+
+impl Setup {
+    /// Applies the layers to the drink and the agitation and humidity
+    /// to the ambience, if the agitation works in its fluid
+    fn prepared(mut self) -> Result<Self, String> {
+        for material in &self.layers {
+            self.drink = self.drink.with_layer(Layer::new(*material));
+        }
+        if !self.agitation.applies_to(self.ambience.fluid) {
+            return Err(format!(
+                "{} doesn't work in {}",
+                agitation_slug(self.agitation),
+                self.ambience.name
+            ));
+        }
+        self.ambience = self.ambience.with_agitation(self.agitation);
+        if let Some(humidity) = self.humidity {
+            self.ambience = self.ambience.with_humidity(humidity.clamp(0., 100.) / 100.);
+        }
+        Ok(self)
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let unit = match cli.fahrenheit {
+        true => TemperatureUnit::DegFahrenheit,
+        false => TemperatureUnit::DegCelsius,
+    };
+
+    let result = match cli.command {
+        Command::Predict { setup, to } => setup.prepared().and_then(|setup| {
+            let to = to.unwrap_or_else(|| setup.drink.serving_target());
+            predict(&setup, &to, unit)
+        }),
+        Command::Curve {
+            setup,
+            minutes,
+            step,
+        } => setup
+            .prepared()
+            .and_then(|setup| curve(&setup, minutes, step.max(1), unit)),
+        Command::Run { setup, to } => setup.prepared().and_then(|setup| {
+            let to = to.unwrap_or_else(|| setup.drink.serving_target());
+            run(&setup, &to, unit)
+        }),
+        Command::List => {
+            list(unit);
+            Ok(())
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn predict(setup: &Setup, target: &Ambience, unit: TemperatureUnit) -> Result<(), String> {
+    let time_needed = checked_time_needed(setup, target, unit)?;
+    println!(
+        "{} {}: {} -> {} in {}",
+        setup.drink.name,
+        setup.drink.description,
+        setup.from.temperature.format(unit, true),
+        target.temperature.format(unit, true),
+        setup.ambience.name,
+    );
+    println!(
+        "Ready after:     {} ({} - {})",
+        format_duration(time_needed.expected),
+        format_duration(time_needed.optimistic),
+        format_duration(time_needed.pessimistic),
+    );
+    let validity =
+        validity(setup.from.temperature, &setup.drink, &setup.ambience).map_err(describe)?;
+    println!(
+        "Biot number:     {:.2}{}",
+        validity.biot_number,
+        match validity.is_lumped() {
+            true => "",
+            false => ", the core lags behind the wall",
+        }
+    );
+
+    let initial = setup.from.temperature;
+    match setup
+        .model
+        .time_until_freezing(initial, &setup.drink, &setup.ambience)
+        .map_err(describe)?
+    {
+        Some(freezing) => println!("Freeze deadline: {}", format_duration(freezing)),
+        None => println!("Freeze deadline: none"),
+    }
+    if let Some(frozen) = setup
+        .model
+        .time_until_frozen(initial, &setup.drink, &setup.ambience)
+        .map_err(describe)?
+    {
+        println!("Frozen solid:    {}", format_duration(frozen));
+    }
+
+    Ok(())
+}
+
+fn curve(setup: &Setup, minutes: u32, step: u32, unit: TemperatureUnit) -> Result<(), String> {
+    println!("minutes\ttemperature\tphase");
+    for minute in (0..=minutes).step_by(step as usize) {
+        let time_passed = Duration::minutes(minute.into());
+        let temperature = setup
+            .model
+            .temperature_after_time(
+                time_passed,
+                setup.from.temperature,
+                &setup.drink,
+                &setup.ambience,
+            )
+            .map_err(describe)?;
+        let phase = setup
+            .model
+            .phase_after_time(
+                time_passed,
+                setup.from.temperature,
+                &setup.drink,
+                &setup.ambience,
+            )
+            .map_err(describe)?;
+        println!(
+            "{minute}\t{:.1}\t{}",
+            temperature.as_unit(unit),
+            format_phase(phase)
+        );
+    }
+
+    Ok(())
+}
+
+fn run(setup: &Setup, target: &Ambience, unit: TemperatureUnit) -> Result<(), String> {
+    let time_needed = checked_time_needed(setup, target, unit)?.expected;
+    let started = Instant::now();
+
+    loop {
+        let elapsed = Duration::from_std(started.elapsed()).unwrap_or_default();
+        let time_left = time_needed - elapsed;
+        if time_left <= Duration::zero() {
+            break;
+        }
+
+        let temperature = setup
+            .model
+            .temperature_after_time(
+                elapsed,
+                setup.from.temperature,
+                &setup.drink,
+                &setup.ambience,
+            )
+            .map_err(describe)?;
+        print!(
+            "\r{} left, {} -> {}   ",
+            format_chrono_duration_precise(time_left),
+            temperature.format(unit, true),
+            target.temperature.format(unit, true),
+        );
+        _ = std::io::stdout().flush();
+        thread::sleep(StdDuration::from_secs(1));
+    }
+
+    // Terminal bell
+    let ready = match is_warming(setup.from.temperature, target.temperature) {
+        true => "ready",
+        false => "cold",
+    };
+    println!("\r{} is {ready}!\x07            ", setup.drink.name);
+
+    Ok(())
+}
+
+fn list(unit: TemperatureUnit) {
+    println!("Drinks:");
+    for (slug, drink) in catalog::DRINKS {
+        let drink = drink();
+        println!(
+            "  {slug:<24} {} {} ({})",
+            drink.name,
+            drink.description,
+            drink.serving_range().format(unit)
+        );
+    }
+
+    println!("Ambiences:");
+    for (slug, ambience) in catalog::AMBIENCES {
+        let ambience = ambience();
+        println!(
+            "  {slug:<24} {} ({})",
+            ambience.name,
+            ambience.temperature.format(unit, true)
+        );
+    }
+}
+
+/// Time until the target temperature with the range it likely falls
+/// into, or why it is never reached
+fn checked_time_needed(
+    setup: &Setup,
+    target: &Ambience,
+    unit: TemperatureUnit,
+) -> Result<ConfidenceBand<Duration>, String> {
+    if !target_reachable(
+        target.temperature,
+        setup.from.temperature,
+        sink_temperature(&setup.drink, &setup.ambience),
+    ) {
+        return Err(format!(
+            "{} can't be reached from {} in {} ({})",
+            target.temperature.format(unit, true),
+            setup.from.temperature.format(unit, true),
+            setup.ambience.name,
+            setup.ambience.temperature.format(unit, true),
+        ));
+    }
+
+    setup
+        .model
+        .time_until_temperature_band(
+            target.temperature,
+            setup.from.temperature,
+            &setup.drink,
+            &setup.ambience,
+        )
+        .map_err(describe)
+}
+
+fn describe(error: CoolingError) -> String {
+    format!("no prediction possible, {error}")
+}
+
+/// Hours and minutes, e.g. `1:05 h`
+fn format_duration(duration: Duration) -> String {
+    format!(
+        "{}:{:02} h",
+        duration.num_hours(),
+        duration.num_minutes() % 60
+    )
+}
+
+fn format_phase(phase: Phase) -> String {
+    match phase {
+        Phase::Liquid => "liquid".to_string(),
+        Phase::Freezing(fraction) => format!("freezing ({:.0} % ice)", fraction * 100.0),
+        Phase::Frozen => "frozen".to_string(),
+    }
+}
+
+fn parse_drink(slug: &str) -> Result<Drink, String> {
+    catalog::drink(slug).ok_or_else(|| format!("unknown drink `{slug}`, see `bier-timer list`"))
+}
+
+/// Known ambience by slug, or plain air of the given temperature
+fn parse_ambience(value: &str) -> Result<Ambience, String> {
+    if let Some(ambience) = catalog::ambience(value) {
+        return Ok(ambience);
+    }
+
+    let temperature = parse_temperature(value)?;
+    Ok(Ambience::new(value, "", temperature, None))
+}
+
+const AGITATIONS: [(&str, Agitation); 4] = [
+    ("still", Agitation::Still),
+    ("stirred", Agitation::Stirred),
+    ("rotated", Agitation::Rotated),
+    ("fan", Agitation::Fan),
+];
+
+fn parse_agitation(value: &str) -> Result<Agitation, String> {
+    AGITATIONS
+        .iter()
+        .find(|(slug, _)| *slug == value)
+        .map(|(_, agitation)| *agitation)
+        .ok_or_else(|| format!("unknown agitation `{value}`"))
+}
+
+fn agitation_slug(agitation: Agitation) -> &'static str {
+    AGITATIONS
+        .iter()
+        .find(|(_, a)| *a == agitation)
+        .map(|(slug, _)| *slug)
+        .unwrap_or_default()
+}
+
+fn parse_model(value: &str) -> Result<CoolingModelKind, String> {
+    match value {
+        "exponential" => Ok(CoolingModelKind::Exponential),
+        "fd" => Ok(CoolingModelKind::FiniteDifference),
+        _ => Err(format!("unknown model `{value}`")),
+    }
+}
+
+fn parse_layer(value: &str) -> Result<LayerMaterial, String> {
+    match value {
+        "label" => Ok(LayerMaterial::PaperLabel),
+        "liner" => Ok(LayerMaterial::PlasticLiner),
+        "koozie" => Ok(LayerMaterial::Neoprene),
+        "wet-towel" => Ok(LayerMaterial::WetTowel),
+        _ => Err(format!("unknown layer `{value}`")),
+    }
+}
+
+/// Number with a unit suffix, e.g. `20C`, `-18 °C`, `68F` or `293K`
+fn parse_temperature(value: &str) -> Result<Temperature, String> {
+    let value = value.trim();
+    let (number, unit) = match value.chars().last().map(|c| c.to_ascii_uppercase()) {
+        Some('C') => (&value[..value.len() - 1], TemperatureUnit::DegCelsius),
+        Some('F') => (&value[..value.len() - 1], TemperatureUnit::DegFahrenheit),
+        Some('K') => (&value[..value.len() - 1], TemperatureUnit::Kelvin),
+        _ => (value, TemperatureUnit::DegCelsius),
+    };
+    let number = number.trim().trim_end_matches('°').trim();
+
+    number
+        .parse()
+        .map(|number| Temperature::new_with_unit(number, unit))
+        .map_err(|_| format!("`{value}` is neither a known ambience nor a temperature"))
+}
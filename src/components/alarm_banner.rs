@@ -0,0 +1,96 @@
+use crate::{
+    app::{CurrentTime, CurrentlyRunningTimers},
+    helpers::alarm::{self, AlarmState},
+};
+use leptos::*;
+
+#[component]
+pub fn AlarmBanner() -> impl IntoView {
+    let currently_running_timers = expect_context::<CurrentlyRunningTimers>().0;
+    let current_time_signal = expect_context::<CurrentTime>().0;
+
+    let ringing_timers = Signal::derive(move || {
+        currently_running_timers
+            .get()
+            .into_iter()
+            .filter(|timer| timer.alarm.get() == AlarmState::Ringing)
+            .collect::<Vec<_>>()
+    });
+
+    view! {
+        <Show when=move || !ringing_timers.get().is_empty() fallback=|| view! { "" }>
+            <div class="alarm_banner">
+
+                {move || {
+                    ringing_timers
+                        .get()
+                        .into_iter()
+                        .map(|timer| {
+                            let alarm_signal = timer.alarm;
+                            view! {
+                                <div class="alarm">
+                                    <span class="info">
+                                        {format!(
+                                            "{} ({}) ist fertig!",
+                                            timer.drink.name,
+                                            timer.drink.description,
+                                        )}
+                                    </span>
+                                    <button
+                                        class="button"
+                                        on:click=move |_| {
+                                            alarm_signal
+                                                .set(
+                                                    AlarmState::Snoozed(
+                                                        current_time_signal.get() + alarm::snooze_duration(),
+                                                    ),
+                                                )
+                                        }
+                                    >
+
+                                        "Schlummern"
+                                    </button>
+                                    <button
+                                        class="button primary"
+                                        on:click=move |_| alarm_signal.set(AlarmState::Dismissed)
+                                    >
+                                        "Aus"
+                                    </button>
+                                </div>
+                            }
+                        })
+                        .collect_view()
+                }}
+
+                {move || {
+                    if alarm::notifications_permitted() {
+                        None
+                    } else if alarm::notifications_denied() {
+                        Some(
+                            view! {
+                                <p class="permission_hint">
+                                    "Benachrichtigungen sind blockiert, der Alarm ist nur hier sichtbar. "
+                                    "Sie lassen sich in den Einstellungen des Browsers für diese Seite erlauben."
+                                </p>
+                            }
+                                .into_view(),
+                        )
+                    } else {
+                        Some(
+                            view! {
+                                <p class="permission_hint">
+                                    "Benachrichtigungen sind blockiert, der Alarm ist nur hier sichtbar. "
+                                    <button class="button" on:click=move |_| alarm::prepare()>
+                                        "Erlauben"
+                                    </button>
+                                </p>
+                            }
+                                .into_view(),
+                        )
+                    }
+                }}
+
+            </div>
+        </Show>
+    }
+}
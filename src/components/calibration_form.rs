@@ -0,0 +1,122 @@
+use crate::{
+    app::{Calibrations, CurrentTime, UnitPreference},
+    helpers::{
+        calibration::{fit_cooling_coefficient, upsert_calibration, Calibration, Measurement},
+        cooling_coefficient, Temperature,
+    },
+    timer_info::TimerInfo,
+};
+use leptos::*;
+
+#[component]
+pub fn CalibrationForm(timer: TimerInfo) -> impl IntoView {
+    let calibrations = expect_context::<Calibrations>().0;
+    let current_time_signal = expect_context::<CurrentTime>().0;
+    let unit_preference = expect_context::<UnitPreference>().0;
+
+    let measurements = timer.measurements;
+    let timer = store_value(timer);
+    let measured_signal = create_rw_signal::<Option<f64>>(None);
+
+    // Only the current segment follows a single exponential curve,
+    // measured from when the drink entered it
+    let fitted_cooling_coefficient = Signal::derive(move || {
+        timer.with_value(|timer| {
+            let (entered, ambience) = timer.segments.with(|segments| {
+                segments.last().map(|segment| {
+                    let entered = segment.timestamp_started - timer.timestamp_started;
+                    (entered.num_milliseconds() as f64 / 1000., segment.ambience.clone())
+                })
+            })?;
+            let start_temperature = timer
+                .course
+                .with(|course| course.as_ref()?.current_start_temperature())?;
+            let measurements = measurements
+                .get()
+                .into_iter()
+                .filter(|measurement| measurement.seconds >= entered)
+                .map(|measurement| Measurement {
+                    seconds: measurement.seconds - entered,
+                    ..measurement
+                })
+                .collect::<Vec<_>>();
+            let cooling_coefficient =
+                fit_cooling_coefficient(&measurements, start_temperature, &timer.drink, &ambience)?;
+            Some((ambience, cooling_coefficient))
+        })
+    });
+
+    let record_measurement = move |_| {
+        let Some(measured) = measured_signal.get() else {
+            return;
+        };
+        timer.with_value(|timer| {
+            let elapsed = current_time_signal.get() - timer.timestamp_started;
+            measurements.update(|measurements| {
+                measurements.push(Measurement {
+                    seconds: elapsed.num_milliseconds() as f64 / 1000.,
+                    temperature: Temperature::new_with_unit(measured, unit_preference.get()),
+                })
+            });
+        });
+        measured_signal.set(None);
+
+        if let Some((ambience, cooling_coefficient)) = fitted_cooling_coefficient.get() {
+            let calibration = timer.with_value(|timer| Calibration {
+                drink_id: timer.drink.id,
+                ambience_id: ambience.id,
+                cooling_coefficient,
+            });
+            calibrations.update(|calibrations| upsert_calibration(calibrations, calibration));
+        }
+    };
+
+    view! {
+        <div class="calibration" on:click=|ev| ev.stop_propagation()>
+            <label>
+                <span class="description">
+                    {move || {
+                        format!("Gemessene Temperatur ({})", unit_preference.get().get_symbol())
+                    }}
+
+                </span>
+                <input
+                    type="number"
+                    step="0.5"
+                    prop:value=move || {
+                        measured_signal.get().map(|value| value.to_string()).unwrap_or_default()
+                    }
+
+                    on:input=move |ev| measured_signal.set(event_target_value(&ev).parse().ok())
+                />
+            </label>
+            <button
+                class="button"
+                disabled=move || measured_signal.get().is_none()
+                on:click=record_measurement
+            >
+                "Messen"
+            </button>
+            <p>
+                {move || format!("{} Messungen", measurements.with(|m| m.len()))}
+                {move || {
+                    fitted_cooling_coefficient
+                        .get()
+                        .and_then(|(ambience, fitted)| {
+                            let predicted = timer
+                                .with_value(|timer| cooling_coefficient(&timer.drink, &ambience))
+                                .ok()?;
+                            Some(
+                                format!(
+                                    ", Abkühlkoeffizient {:.2e} 1/s ({:+.0} % zum Modell)",
+                                    fitted,
+                                    (fitted / predicted - 1.0) * 100.0,
+                                ),
+                            )
+                        })
+                }}
+
+            </p>
+        </div>
+    }
+}
@@ -0,0 +1,327 @@
+use crate::{
+    app::{CustomDrinks, UnitPreference},
+    helpers::{
+        drink::{
+            Container, ContainerMaterial, ContainerShape, DrinkType, HeatTransferCoefficient,
+            Layer, LayerMaterial,
+        },
+        milliliters_to_m3,
+        serving::DrinkStyle,
+        Drink, Fluid,
+    },
+};
+use leptos::*;
+
+#[component]
+pub fn DrinkEditor() -> impl IntoView {
+    let custom_drinks = expect_context::<CustomDrinks>().0;
+    let unit_preference = expect_context::<UnitPreference>().0;
+
+    let name_signal = create_rw_signal(String::from("Eigenes Getränk"));
+    let material_signal = create_rw_signal(ContainerMaterial::Glass);
+    let shape_signal = create_rw_signal(ContainerShape::BeerBottle);
+    let volume_signal = create_rw_signal(500.0);
+    let alcohol_signal = create_rw_signal(5.0);
+    let sugar_signal = create_rw_signal(0.0);
+    let type_signal = create_rw_signal(DrinkType::Beer);
+    let style_signal = create_rw_signal::<Option<DrinkStyle>>(None);
+    // `None` keeps the usual wall of the material
+    let wall_thickness_signal = create_rw_signal::<Option<f64>>(None);
+    let layers_signal = create_rw_signal::<Vec<LayerMaterial>>(vec![]);
+
+    let valid = Signal::derive(move || {
+        !name_signal.get().trim().is_empty()
+            && volume_signal.get() > 0.0
+            && (0.0..=100.0).contains(&alcohol_signal.get())
+            && (0.0..=500.0).contains(&sugar_signal.get())
+            && wall_thickness_signal
+                .get()
+                .is_none_or(|thickness| thickness > 0.0)
+    });
+
+    let drink = Signal::derive(move || {
+        let shape = shape_signal.get();
+        let mut container = Container::new(
+            milliliters_to_m3(volume_signal.get()),
+            material_signal.get(),
+            shape,
+        );
+        if let Some(thickness) = wall_thickness_signal.get() {
+            container = container.with_wall_thickness(thickness / 1000.0);
+        }
+        for material in layers_signal.get() {
+            container = container.with_layer(Layer::new(material));
+        }
+        let drink = Drink::new(
+            name_signal.get().trim(),
+            &format!("{:.0}ml {}", volume_signal.get(), shape.get_name()),
+            shape.get_path_to_image(),
+            container,
+            alcohol_signal.get() / 100.0,
+            sugar_signal.get(),
+        );
+        match style_signal.get() {
+            Some(style) => drink.with_style(style),
+            None => drink.with_type(type_signal.get()),
+        }
+    });
+
+    view! {
+        <div class="drink_editor">
+            <label>
+                <span class="description">"Name"</span>
+                <input
+                    type="text"
+                    prop:value=move || name_signal.get()
+                    on:input=move |ev| name_signal.set(event_target_value(&ev))
+                />
+            </label>
+            <label>
+                <span class="description">"Art"</span>
+                <select on:change=move |ev| {
+                    if let Some(drink_type) = DrinkType::ALL
+                        .get(event_target_value(&ev).parse::<usize>().unwrap_or_default())
+                    {
+                        type_signal.set(*drink_type);
+                        style_signal.set(None);
+                    }
+                }>
+
+                    {DrinkType::ALL
+                        .iter()
+                        .enumerate()
+                        .map(|(index, drink_type)| {
+                            view! {
+                                <option
+                                    value=index
+                                    selected=move || type_signal.get() == *drink_type
+                                >
+                                    {drink_type.get_name()}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+
+                </select>
+            </label>
+            <label>
+                <span class="description">"Sorte"</span>
+                <select on:change=move |ev| {
+                    style_signal
+                        .set(
+                            event_target_value(&ev)
+                                .parse::<usize>()
+                                .ok()
+                                .and_then(|index| DrinkStyle::ALL.get(index).copied()),
+                        )
+                }>
+                    <option value="none" selected=move || style_signal.get().is_none()>
+                        "Keine Angabe"
+                    </option>
+                    {move || {
+                        DrinkStyle::ALL
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, style)| style.drink_type() == type_signal.get())
+                            .map(|(index, style)| {
+                                view! {
+                                    <option
+                                        value=index
+                                        selected=move || style_signal.get() == Some(*style)
+                                    >
+                                        {style.get_name()}
+                                    </option>
+                                }
+                            })
+                            .collect_view()
+                    }}
+
+                </select>
+            </label>
+            <label>
+                <span class="description">"Behälter"</span>
+                <select on:change=move |ev| {
+                    if let Some(shape) = ContainerShape::ALL
+                        .get(event_target_value(&ev).parse::<usize>().unwrap_or_default())
+                    {
+                        shape_signal.set(*shape);
+                    }
+                }>
+
+                    {ContainerShape::ALL
+                        .iter()
+                        .enumerate()
+                        .map(|(index, shape)| {
+                            view! {
+                                <option value=index selected=move || shape_signal.get() == *shape>
+                                    {shape.get_name()}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+
+                </select>
+            </label>
+            <label>
+                <span class="description">"Material"</span>
+                <select on:change=move |ev| {
+                    if let Some(material) = ContainerMaterial::ALL
+                        .get(event_target_value(&ev).parse::<usize>().unwrap_or_default())
+                    {
+                        material_signal.set(*material);
+                    }
+                }>
+
+                    {ContainerMaterial::ALL
+                        .iter()
+                        .enumerate()
+                        .map(|(index, material)| {
+                            view! {
+                                <option
+                                    value=index
+                                    selected=move || material_signal.get() == *material
+                                >
+                                    {material.get_name()}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+
+                </select>
+            </label>
+            <label>
+                <span class="description">"Wandstärke (mm)"</span>
+                <input
+                    type="number"
+                    min="0.1"
+                    step="0.1"
+                    placeholder=move || format!("{:.1}", material_signal.get().get_thickness() * 1000.0)
+                    prop:value=move || {
+                        wall_thickness_signal.get().map(|thickness| thickness.to_string()).unwrap_or_default()
+                    }
+
+                    on:input=move |ev| {
+                        wall_thickness_signal.set(event_target_value(&ev).parse().ok())
+                    }
+                />
+
+            </label>
+            <fieldset class="layers">
+                <legend class="description">"Umhüllung"</legend>
+                {LayerMaterial::ALL
+                    .iter()
+                    .map(|material| {
+                        let material = *material;
+                        view! {
+                            <label>
+                                <input
+                                    type="checkbox"
+                                    prop:checked=move || layers_signal.get().contains(&material)
+                                    on:change=move |ev| {
+                                        let checked = event_target_checked(&ev);
+                                        layers_signal
+                                            .update(|layers| {
+                                                layers.retain(|layer| *layer != material);
+                                                if checked {
+                                                    layers.push(material);
+                                                }
+                                            })
+                                    }
+                                />
+
+                                {material.get_name()}
+                            </label>
+                        }
+                    })
+                    .collect_view()}
+            </fieldset>
+            <label>
+                <span class="description">"Volumen (ml)"</span>
+                <input
+                    type="number"
+                    min="1"
+                    step="10"
+                    prop:value=move || volume_signal.get()
+                    on:input=move |ev| {
+                        volume_signal.set(event_target_value(&ev).parse().unwrap_or_default())
+                    }
+                />
+
+            </label>
+            <label>
+                <span class="description">"Alkohol (% vol)"</span>
+                <input
+                    type="number"
+                    min="0"
+                    max="100"
+                    step="0.5"
+                    prop:value=move || alcohol_signal.get()
+                    on:input=move |ev| {
+                        alcohol_signal.set(event_target_value(&ev).parse().unwrap_or_default())
+                    }
+                />
+
+            </label>
+            <label>
+                <span class="description">"Zucker (g/l)"</span>
+                <input
+                    type="number"
+                    min="0"
+                    max="500"
+                    step="5"
+                    prop:value=move || sugar_signal.get()
+                    on:input=move |ev| {
+                        sugar_signal.set(event_target_value(&ev).parse().unwrap_or_default())
+                    }
+                />
+
+            </label>
+
+            <div class="preview">
+                <Show when=move || valid.get() fallback=|| view! { <p>"Ungültige Eingabe"</p> }>
+                    <p>
+                        "Abkühlkoeffizient Luft: "
+                        {move || {
+                            format!(
+                                "{:.2e} 1/s",
+                                drink.get().cooling_coefficient(Fluid::Air.get_heat_transfer_coefficient()),
+                            )
+                        }}
+
+                    </p>
+                    <p>
+                        "Abkühlkoeffizient Wasser: "
+                        {move || {
+                            format!(
+                                "{:.2e} 1/s",
+                                drink.get().cooling_coefficient(Fluid::Water.get_heat_transfer_coefficient()),
+                            )
+                        }}
+
+                    </p>
+                    <p>
+                        "Gefrierpunkt: "
+                        {move || drink.get().freezing_point.format(unit_preference.get(), true)}
+                    </p>
+                    <p>
+                        "Trinktemperatur: "
+                        {move || drink.get().serving_range().format(unit_preference.get())}
+                    </p>
+                </Show>
+            </div>
+
+            <button
+                class="button primary"
+                disabled=move || !valid.get()
+                on:click=move |_| {
+                    if valid.get() {
+                        custom_drinks.update(|drinks| drinks.push(drink.get()));
+                    }
+                }
+            >
+
+                "Getränk speichern"
+            </button>
+        </div>
+    }
+}
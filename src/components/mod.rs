@@ -1,5 +1,17 @@
+mod alarm_banner;
+mod calibration_form;
+mod drink_editor;
+mod model_switch;
+mod planner;
+mod preset_builder;
 mod preset_summary;
+mod temperature_chart;
+mod timer_controls;
 mod timer_preset_button;
 mod timer_tile;
+mod unit_switch;
 
-pub use self::{preset_summary::*, timer_preset_button::*, timer_tile::*};
+pub use self::{
+    alarm_banner::*, calibration_form::*, drink_editor::*, model_switch::*, planner::*, preset_builder::*,
+    preset_summary::*, temperature_chart::*, timer_controls::*, timer_preset_button::*, timer_tile::*, unit_switch::*,
+};
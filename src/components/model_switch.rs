@@ -0,0 +1,29 @@
+use crate::{app::ModelPreference, helpers::model::CoolingModelKind};
+use leptos::*;
+
+#[component]
+pub fn ModelSwitch() -> impl IntoView {
+    let model_preference = expect_context::<ModelPreference>().0;
+
+    view! {
+        <div class="model_switch" title="Berechnungsmodell für neue Timer">
+
+            {CoolingModelKind::ALL
+                .iter()
+                .map(|model| {
+                    let model = *model;
+                    view! {
+                        <button
+                            class="button"
+                            class:primary=move || model_preference.get() == model
+                            on:click=move |_| model_preference.set(model)
+                        >
+                            {model.get_name()}
+                        </button>
+                    }
+                })
+                .collect_view()}
+
+        </div>
+    }
+}
@@ -0,0 +1,217 @@
+use chrono::{DateTime, Local, NaiveTime};
+use leptos::*;
+use uuid::Uuid;
+
+use crate::{
+    app::{
+        Calibrations, CurrentTime, CurrentlyRunningTimers, CustomTimerPresets, ModelPreference,
+        PlannedDrinks,
+    },
+    helpers::{
+        alarm,
+        model::CoolingModel,
+        plan::{next_occurrence, staggered_schedule, PlannedDrink},
+        CoolingError, TimerPreset,
+    },
+    timer_info::TimerInfo,
+};
+
+#[component]
+pub fn Planner(#[prop(into)] timer_presets: Signal<Vec<TimerPreset>>) -> impl IntoView {
+    let custom_timer_presets = expect_context::<CustomTimerPresets>().0;
+    let planned_drinks = expect_context::<PlannedDrinks>().0;
+    let currently_running_timers = expect_context::<CurrentlyRunningTimers>().0;
+    let current_time_signal = expect_context::<CurrentTime>().0;
+    let calibrations = expect_context::<Calibrations>().0;
+    let model_preference = expect_context::<ModelPreference>().0;
+
+    let all_presets = Signal::derive(move || {
+        timer_presets
+            .get()
+            .into_iter()
+            .chain(custom_timer_presets.get())
+            .collect::<Vec<_>>()
+    });
+
+    let preset_id_signal = create_rw_signal::<Option<Uuid>>(None);
+    let plan_error_signal = create_rw_signal::<Option<CoolingError>>(None);
+    let ready_time_signal =
+        create_rw_signal(NaiveTime::from_hms_opt(19, 30, 0).expect("valid time"));
+
+    let selected_preset = Signal::derive(move || {
+        let presets = all_presets.get();
+        preset_id_signal
+            .get()
+            .and_then(|id| presets.iter().find(|preset| preset.id == id))
+            .or(presets.first())
+            .cloned()
+    });
+
+    let add_plan = move |_| {
+        let Some(mut preset) = selected_preset.get() else {
+            return;
+        };
+        // Asked now, so the reminder can ring later
+        alarm::prepare();
+        preset.drink = preset
+            .drink
+            .calibrated(&preset.ambient_ambience, &calibrations.get());
+        let ready_at = next_occurrence(ready_time_signal.get(), current_time_signal.get());
+        match PlannedDrink::new(preset, model_preference.get(), ready_at) {
+            Ok(plan) => {
+                plan_error_signal.set(None);
+                planned_drinks.update(|plans| plans.push(plan));
+            }
+            Err(error) => plan_error_signal.set(Some(error)),
+        }
+    };
+
+    let start_timer = move |plan: PlannedDrink| {
+        let timer = TimerInfo::new(plan.preset, plan.model, current_time_signal);
+        currently_running_timers.update(move |timers| timers.push(timer));
+        planned_drinks.update(|plans| plans.retain(|p| p.id != plan.id));
+    };
+
+    let format_time = |time: DateTime<Local>| time.format("%H:%M").to_string();
+
+    view! {
+        <div class="planner">
+            <div class="planner_form">
+                <label>
+                    <span class="description">"Timer"</span>
+                    <select on:change=move |ev| {
+                        plan_error_signal.set(None);
+                        preset_id_signal.set(event_target_value(&ev).parse().ok())
+                    }>
+                        {move || {
+                            let selected_id = selected_preset.get().map(|preset| preset.id);
+                            all_presets
+                                .get()
+                                .into_iter()
+                                .map(|preset| {
+                                    view! {
+                                        <option
+                                            value=preset.id.to_string()
+                                            selected=Some(preset.id) == selected_id
+                                        >
+                                            {format!("{} - {}", preset.name, preset.drink.description)}
+                                        </option>
+                                    }
+                                })
+                                .collect_view()
+                        }}
+
+                    </select>
+                </label>
+                <label>
+                    <span class="description">"Trinkbereit um"</span>
+                    <input
+                        type="time"
+                        prop:value=move || ready_time_signal.get().format("%H:%M").to_string()
+                        on:input=move |ev| {
+                            if let Ok(time) = NaiveTime::parse_from_str(
+                                &event_target_value(&ev),
+                                "%H:%M",
+                            ) {
+                                ready_time_signal.set(time);
+                            }
+                        }
+                    />
+
+                </label>
+                <button
+                    class="button primary"
+                    disabled=move || selected_preset.get().is_none()
+                    on:click=add_plan
+                >
+                    "Einplanen"
+                </button>
+            </div>
+            {move || {
+                plan_error_signal
+                    .get()
+                    .map(|error| view! { <p class="plan_error">{error.get_description()}</p> })
+            }}
+
+            {move || {
+                let now = current_time_signal.get();
+                staggered_schedule(&planned_drinks.get())
+                    .into_iter()
+                    .map(|(ready_at, group)| {
+                        view! {
+                            <div class="schedule">
+                                <h4>{format!("Trinkbereit um {}", format_time(ready_at))}</h4>
+
+                                {group
+                                    .into_iter()
+                                    .map(|plan| {
+                                        let due = plan.is_due(now);
+                                        // Starting a late drink now moves its ready time
+                                        let late_ready_at = plan
+                                            .model
+                                            .time_until_temperature(
+                                                plan.preset.target_ambience.temperature,
+                                                plan.preset.initial_ambience.temperature,
+                                                &plan.preset.drink,
+                                                &plan.preset.ambient_ambience,
+                                            )
+                                            .ok()
+                                            .map(|time_needed| now + time_needed)
+                                            .filter(|late_ready_at| *late_ready_at > plan.ready_at);
+                                        let plan_id = plan.id;
+                                        let start_plan = plan.clone();
+                                        view! {
+                                            <div class="planned_drink" class:due=due>
+                                                <span class="put_in_at">
+                                                    {format_time(plan.put_in_at)}
+                                                </span>
+                                                <span class="info">
+                                                    {format!(
+                                                        "{} - {} {}",
+                                                        plan.preset.name,
+                                                        plan.preset.drink.description,
+                                                        match plan.preset.is_warming() {
+                                                            true => "raus",
+                                                            false => "rein",
+                                                        },
+                                                    )}
+                                                    {late_ready_at
+                                                        .filter(|_| due)
+                                                        .map(|late_ready_at| {
+                                                            format!(
+                                                                " (zu spät, trinkbereit um {})",
+                                                                format_time(late_ready_at),
+                                                            )
+                                                        })}
+
+                                                </span>
+                                                <button
+                                                    class="button"
+                                                    class:primary=due
+                                                    on:click=move |_| start_timer(start_plan.clone())
+                                                >
+                                                    "Reingelegt"
+                                                </button>
+                                                <button
+                                                    class="button danger"
+                                                    on:click=move |_| {
+                                                        planned_drinks
+                                                            .update(|plans| plans.retain(|p| p.id != plan_id))
+                                                    }
+                                                >
+
+                                                    "Entfernen"
+                                                </button>
+                                            </div>
+                                        }
+                                    })
+                                    .collect_view()}
+                            </div>
+                        }
+                    })
+                    .collect_view()
+            }}
+
+        </div>
+    }
+}
@@ -0,0 +1,458 @@
+use crate::{
+    app::{
+        BuiltinDrinks, Calibrations, CustomDrinks, CustomTimerPresets, KnownAmbiences,
+        ModelPreference, UnitPreference,
+    },
+    helpers::{
+        format_chrono_duration_simple, model::CoolingModel, Ambience, Drink, Temperature,
+        TemperatureUnit, TimerPreset,
+    },
+};
+use leptos::*;
+use uuid::Uuid;
+
+/// Whether the ambience is the known one with the agitation and
+/// humidity picked in the preset summary on top
+fn builds_on(known: &Ambience, ambience: &Ambience) -> bool {
+    let mut known = known.clone().with_agitation(ambience.agitation);
+    if let Some(humidity) = ambience.humidity {
+        known = known.with_humidity(humidity);
+    }
+    known.id == ambience.id
+}
+
+#[component]
+pub fn PresetBuilder(editing_preset_signal: RwSignal<Option<TimerPreset>>) -> impl IntoView {
+    let builtin_drinks = expect_context::<BuiltinDrinks>().0;
+    let custom_drinks = expect_context::<CustomDrinks>().0;
+    let custom_timer_presets = expect_context::<CustomTimerPresets>().0;
+    let ambiences = expect_context::<KnownAmbiences>();
+    let unit_preference = expect_context::<UnitPreference>().0;
+    let calibrations = expect_context::<Calibrations>().0;
+    let model_preference = expect_context::<ModelPreference>().0;
+
+    let all_drinks = Signal::derive(move || {
+        builtin_drinks
+            .iter()
+            .cloned()
+            .chain(custom_drinks.get())
+            .collect::<Vec<_>>()
+    });
+
+    let name_signal = create_rw_signal(String::from("Eigener Timer"));
+    let drink_id_signal = create_rw_signal::<Option<Uuid>>(None);
+    // `None` means the ambience of an edited preset is no longer known
+    let initial_index_signal = create_rw_signal::<Option<usize>>(Some(0));
+    let ambient_index_signal = create_rw_signal::<Option<usize>>(Some(0));
+    // `None` means a free target temperature is used
+    let target_index_signal = create_rw_signal::<Option<usize>>(Some(0));
+    let custom_target_signal =
+        create_rw_signal(Temperature::new_with_unit(6.0, TemperatureUnit::DegCelsius));
+    // What is typed into the custom target, partial input like `-`
+    // stays until it parses
+    let custom_target_text = create_rw_signal(String::new());
+    create_effect(move |_| {
+        let value = custom_target_signal.get().as_unit(unit_preference.get());
+        let typed = custom_target_text
+            .get_untracked()
+            .trim()
+            .replace(',', ".")
+            .parse::<f64>();
+        if typed.map_or(true, |typed| (typed - value).abs() > 1e-9) {
+            custom_target_text.set(format!("{value:.1}"));
+        }
+    });
+
+    // Load an existing preset into the form
+    let ambiences_copy = ambiences.clone();
+    create_effect(move |_| {
+        let Some(preset) = editing_preset_signal.get() else {
+            return;
+        };
+        let position =
+            |list: &[Ambience], ambience: &Ambience| list.iter().position(|a| a.id == ambience.id);
+
+        name_signal.set(preset.name.clone());
+        drink_id_signal.set(Some(preset.drink.id));
+        initial_index_signal.set(position(&ambiences_copy.initial, &preset.initial_ambience));
+        ambient_index_signal.set(
+            ambiences_copy
+                .ambient
+                .iter()
+                .position(|a| builds_on(a, &preset.ambient_ambience)),
+        );
+        target_index_signal.set(position(&ambiences_copy.target, &preset.target_ambience));
+        custom_target_signal.set(preset.target_ambience.temperature);
+    });
+
+    // Choosing a drink proposes its ideal serving temperature, as a
+    // known target if one matches
+    let ambiences_copy = ambiences.clone();
+    let propose_target = move |drink: &Drink| {
+        let ideal = drink.serving_range().ideal;
+        match ambiences_copy
+            .target
+            .iter()
+            .position(|a| (a.temperature.as_kelvin() - ideal.as_kelvin()).abs() < 0.01)
+        {
+            Some(index) => target_index_signal.set(Some(index)),
+            None => {
+                target_index_signal.set(None);
+                custom_target_signal.set(ideal);
+            }
+        }
+    };
+
+    let ambiences_copy = ambiences.clone();
+    let preset = Signal::derive(move || {
+        let drinks = all_drinks.get();
+        let drink = drink_id_signal
+            .get()
+            .and_then(|id| drinks.iter().find(|drink| drink.id == id))
+            .or(drinks.first())
+            .cloned()
+            .expect("at least one known drink");
+
+        let target_ambience = match target_index_signal.get() {
+            Some(index) => ambiences_copy.target[index].clone(),
+            None => Ambience::new(
+                "Eigene Zieltemperatur",
+                "./assets/images/ioicon/thermometer-outline.svg",
+                custom_target_signal.get(),
+                None,
+            ),
+        };
+
+        let initial_ambience =
+            ambiences_copy.initial[initial_index_signal.get().unwrap_or_default()].clone();
+        let ambient_ambience =
+            ambiences_copy.ambient[ambient_index_signal.get().unwrap_or_default()].clone();
+
+        match editing_preset_signal.get() {
+            // Keeps what the form doesn't show, like the agitation and
+            // rotation reminder picked in the preset summary
+            Some(editing) => TimerPreset {
+                name: String::from(name_signal.get().trim()),
+                path_to_image: drink.path_to_image.clone(),
+                ambient_ambience: match builds_on(&ambient_ambience, &editing.ambient_ambience) {
+                    true => editing.ambient_ambience.clone(),
+                    false => ambient_ambience,
+                },
+                drink,
+                initial_ambience,
+                target_ambience,
+                ..editing
+            },
+            None => TimerPreset::new(
+                name_signal.get().trim(),
+                &drink.path_to_image.clone(),
+                drink,
+                initial_ambience,
+                ambient_ambience,
+                target_ambience,
+            ),
+        }
+    });
+
+    let time_needed = Signal::derive(move || {
+        let preset = preset.get();
+        model_preference.get().time_until_temperature(
+            preset.target_ambience.temperature,
+            preset.initial_ambience.temperature,
+            &preset
+                .drink
+                .calibrated(&preset.ambient_ambience, &calibrations.get()),
+            &preset.ambient_ambience,
+        )
+    });
+
+    // Parts of an edited preset that are no longer known, the preview
+    // stands in the first known ones but saving must not
+    let drink_missing = Signal::derive(move || {
+        drink_id_signal
+            .get()
+            .is_some_and(|id| all_drinks.with(|drinks| drinks.iter().all(|drink| drink.id != id)))
+    });
+    let missing = Signal::derive(move || {
+        [
+            (drink_missing.get(), "Getränk"),
+            (initial_index_signal.get().is_none(), "Ausgangstemperatur"),
+            (ambient_index_signal.get().is_none(), "Kühltemperatur"),
+        ]
+        .into_iter()
+        .filter_map(|(missing, part)| missing.then_some(part))
+        .collect::<Vec<_>>()
+    });
+
+    let valid = Signal::derive(move || {
+        let preset = preset.get();
+        !preset.name.is_empty() && time_needed.get().is_ok() && missing.with(Vec::is_empty)
+    });
+
+    let ambience_options = move |list: Vec<Ambience>, selected: RwSignal<Option<usize>>| {
+        let options = list
+            .into_iter()
+            .enumerate()
+            .map(|(index, ambience)| {
+                view! {
+                    <option value=index selected=move || selected.get() == Some(index)>
+                        {move || {
+                            format!(
+                                "{} ({})",
+                                ambience.name,
+                                ambience.temperature.format(unit_preference.get(), true),
+                            )
+                        }}
+
+                    </option>
+                }
+            })
+            .collect_view();
+        view! {
+            {move || {
+                selected
+                    .get()
+                    .is_none()
+                    .then(|| {
+                        view! {
+                            <option value="" selected disabled>
+                                "Nicht mehr vorhanden"
+                            </option>
+                        }
+                    })
+            }}
+
+            {options}
+        }
+    };
+
+    view! {
+        <div class="preset_builder">
+            <label>
+                <span class="description">"Name"</span>
+                <input
+                    type="text"
+                    prop:value=move || name_signal.get()
+                    on:input=move |ev| name_signal.set(event_target_value(&ev))
+                />
+            </label>
+            <label>
+                <span class="description">"Getränk"</span>
+                <select on:change=move |ev| {
+                    let id = event_target_value(&ev).parse().ok();
+                    drink_id_signal.set(id);
+                    if let Some(drink) = all_drinks
+                        .get()
+                        .iter()
+                        .find(|drink| Some(drink.id) == id)
+                    {
+                        propose_target(drink);
+                    }
+                }>
+                    {move || {
+                        drink_missing
+                            .get()
+                            .then(|| {
+                                view! {
+                                    <option value="" selected disabled>
+                                        {editing_preset_signal
+                                            .get()
+                                            .map(|preset| {
+                                                format!(
+                                                    "{} - {} (nicht mehr vorhanden)",
+                                                    preset.drink.name,
+                                                    preset.drink.description,
+                                                )
+                                            })}
+
+                                    </option>
+                                }
+                            })
+                    }}
+
+                    {move || {
+                        let selected_id = preset.get().drink.id;
+                        let drink_missing = drink_missing.get();
+                        all_drinks
+                            .get()
+                            .into_iter()
+                            .map(|drink| {
+                                view! {
+                                    <option
+                                        value=drink.id.to_string()
+                                        selected=!drink_missing && drink.id == selected_id
+                                    >
+                                        {format!("{} - {}", drink.name, drink.description)}
+                                    </option>
+                                }
+                            })
+                            .collect_view()
+                    }}
+
+                </select>
+            </label>
+            <label>
+                <span class="description">"Ausgangstemperatur"</span>
+                <select on:change=move |ev| {
+                    initial_index_signal.set(event_target_value(&ev).parse().ok())
+                }>
+                    {ambience_options(ambiences.initial.clone(), initial_index_signal)}
+                </select>
+            </label>
+            <label>
+                <span class="description">
+                    {move || match preset.get().is_warming() {
+                        true => "Umgebungstemperatur",
+                        false => "Kühltemperatur",
+                    }}
+
+                </span>
+                <select on:change=move |ev| {
+                    ambient_index_signal.set(event_target_value(&ev).parse().ok())
+                }>
+                    {ambience_options(ambiences.ambient.clone(), ambient_index_signal)}
+                </select>
+            </label>
+            <label>
+                <span class="description">"Zieltemperatur"</span>
+                <select on:change=move |ev| {
+                    target_index_signal.set(event_target_value(&ev).parse().ok())
+                }>
+
+                    {ambiences
+                        .target
+                        .iter()
+                        .cloned()
+                        .enumerate()
+                        .map(|(index, ambience)| {
+                            view! {
+                                <option
+                                    value=index
+                                    selected=move || target_index_signal.get() == Some(index)
+                                >
+                                    {move || {
+                                        format!(
+                                            "{} ({})",
+                                            ambience.name,
+                                            ambience.temperature.format(unit_preference.get(), true),
+                                        )
+                                    }}
+
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                    <option value="custom" selected=move || target_index_signal.get().is_none()>
+                        "Eigene Temperatur"
+                    </option>
+                </select>
+            </label>
+            <Show when=move || target_index_signal.get().is_none() fallback=|| view! { "" }>
+                <label>
+                    <span class="description">
+                        {move || {
+                            format!("Eigene Zieltemperatur ({})", unit_preference.get().get_symbol())
+                        }}
+
+                    </span>
+                    <input
+                        type="text"
+                        inputmode="decimal"
+                        prop:value=move || custom_target_text.get()
+                        on:input=move |ev| {
+                            let text = event_target_value(&ev);
+                            if let Ok(value) = text.trim().replace(',', ".").parse::<f64>() {
+                                custom_target_signal
+                                    .set(Temperature::new_with_unit(value, unit_preference.get()));
+                            }
+                            custom_target_text.set(text);
+                        }
+                    />
+
+                </label>
+            </Show>
+
+            <div class="preview">
+                <Show
+                    when=move || valid.get()
+                    fallback=move || {
+                        view! {
+                            <p>
+                                {move || {
+                                    let missing = missing.get();
+                                    if !missing.is_empty() {
+                                        return format!(
+                                            "{} nicht mehr vorhanden, bitte neu wählen",
+                                            missing.join(", "),
+                                        );
+                                    }
+                                    match time_needed.get() {
+                                        Ok(_) => "Der Timer braucht einen Namen",
+                                        Err(error) => error.get_description(),
+                                    }
+                                        .to_string()
+                                }}
+
+                            </p>
+                        }
+                    }
+                >
+
+                    <p>
+                        "Dauer: "
+                        {move || {
+                            time_needed.get().map(format_chrono_duration_simple).unwrap_or_default()
+                        }}
+
+                    </p>
+                    <p>
+                        "Trinktemperatur: "
+                        {move || {
+                            let preset = preset.get();
+                            let range = preset.drink.serving_range();
+                            format!(
+                                "{} (Ziel {})",
+                                range.format(unit_preference.get()),
+                                range.band(preset.target_ambience.temperature).get_name(),
+                            )
+                        }}
+
+                    </p>
+                </Show>
+            </div>
+
+            <div class="actions">
+                <button
+                    class="button primary"
+                    disabled=move || !valid.get()
+                    on:click=move |_| {
+                        if !valid.get() {
+                            return;
+                        }
+                        let preset = preset.get();
+                        custom_timer_presets
+                            .update(|presets| {
+                                match presets.iter_mut().find(|p| p.id == preset.id) {
+                                    Some(existing) => *existing = preset,
+                                    None => presets.push(preset),
+                                }
+                            });
+                        editing_preset_signal.set(None);
+                    }
+                >
+
+                    {move || match editing_preset_signal.get() {
+                        Some(_) => "Timer aktualisieren",
+                        None => "Timer speichern",
+                    }}
+
+                </button>
+                <Show when=move || editing_preset_signal.get().is_some() fallback=|| view! { "" }>
+                    <button class="button" on:click=move |_| editing_preset_signal.set(None)>
+                        "Abbrechen"
+                    </button>
+                </Show>
+            </div>
+        </div>
+    }
+}
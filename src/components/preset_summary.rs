@@ -1,8 +1,18 @@
 use crate::{
-    app::{CurrentTime, CurrentlyRunningTimers},
-    helpers::{format_chrono_duration_simple, time_until_temperature, TimerPreset},
+    app::{Calibrations, CurrentTime, CurrentlyRunningTimers, ModelPreference, UnitPreference},
+    components::{sample_curve, TemperatureChart},
+    helpers::{
+        alarm,
+        ambience::Agitation,
+        drink::{Layer, LayerMaterial},
+        evaporation::{evaporation, DEFAULT_HUMIDITY},
+        model::{CoolingModel, CoolingModelKind},
+        validity::validity,
+        TimerPreset,
+    },
     timer_info::TimerInfo,
 };
+use chrono::Duration;
 use leptos::*;
 
 #[component]
@@ -10,17 +20,130 @@ pub fn PresetSummary(
     preset_signal: RwSignal<TimerPreset>,
     modal_showing_signal: RwSignal<bool>,
 ) -> impl IntoView {
-    let time_needed = Signal::derive(move || {
-        time_until_temperature(
-            preset_signal.get().target_ambience.temperature,
-            preset_signal.get().initial_ambience.temperature,
-            &preset_signal.get().drink,
-            &preset_signal.get().ambient_ambience,
+    let calibrations = expect_context::<Calibrations>().0;
+    let model_preference = expect_context::<ModelPreference>().0;
+    // Drink wrapped in a wet towel before it goes in
+    let wet_towel_signal = create_rw_signal(false);
+    // Preset with the chosen preparation and the user's calibration
+    // applied to its drink
+    let prepared_preset = move |wet_towel: bool| {
+        let mut preset = preset_signal.get();
+        if wet_towel {
+            preset.drink = preset.drink.with_layer(Layer::new(LayerMaterial::WetTowel));
+        }
+        preset.drink = preset
+            .drink
+            .calibrated(&preset.ambient_ambience, &calibrations.get());
+        preset
+    };
+    let calibrated_preset = Signal::derive(move || prepared_preset(wet_towel_signal.get()));
+
+    // Range the drink is likely ready in, the expected time in between
+    let time_needed_band = Signal::derive(move || {
+        let preset = calibrated_preset.get();
+        model_preference.get().time_until_temperature_band(
+            preset.target_ambience.temperature,
+            preset.initial_ambience.temperature,
+            &preset.drink,
+            &preset.ambient_ambience,
         )
     });
+    let time_needed = Signal::derive(move || time_needed_band.get().map(|band| band.expected));
+
+    // The core lags behind the wall, which only the finite-difference model sees
+    let core_lags = Signal::derive(move || {
+        let preset = calibrated_preset.get();
+        model_preference.get() == CoolingModelKind::Exponential
+            && validity(
+                preset.initial_ambience.temperature,
+                &preset.drink,
+                &preset.ambient_ambience,
+            )
+            .is_ok_and(|validity| !validity.is_lumped())
+    });
+
+    let chart_curve = Signal::derive(move || {
+        let preset = calibrated_preset.get();
+        let Ok(time_needed) = time_needed.get() else {
+            return vec![];
+        };
+        let span = time_needed * 6 / 5;
+        // One run of the model for all points of the curve
+        let Ok(trajectory) = model_preference.get().trajectory(
+            span,
+            preset.initial_ambience.temperature,
+            &preset.drink,
+            &preset.ambient_ambience,
+        ) else {
+            return vec![];
+        };
+        sample_curve(span, 60, |time| {
+            trajectory
+                .temperature_after_time(time)
+                .unwrap_or(preset.initial_ambience.temperature)
+        })
+    });
+
+    // Salt for an unsalted ice bath and the minutes it saves
+    let salt_tip = Signal::derive(move || {
+        let preset = calibrated_preset.get();
+        let bath = preset.ambient_ambience.ice_bath?;
+        if bath.salt_mass > 0. {
+            return None;
+        }
+
+        let salted = bath
+            .with_salt(bath.suggested_salt())
+            .to_ambience()
+            .with_agitation(preset.ambient_ambience.agitation);
+        let time_salted = model_preference
+            .get()
+            .time_until_temperature(
+                preset.target_ambience.temperature,
+                preset.initial_ambience.temperature,
+                &preset.drink,
+                &salted,
+            )
+            .ok()?;
+        let saved = time_needed.get().ok()? - time_salted;
+        (saved >= Duration::minutes(1)).then_some((salted, bath.suggested_salt(), saved))
+    });
+
+    // Minutes the wet towel saves, `None` if nothing evaporates in the
+    // ambience or the drink warms up, which evaporation only slows down
+    let wet_towel_saving = Signal::derive(move || {
+        if preset_signal.get().is_warming() {
+            return None;
+        }
+        let time_for = |wet_towel| {
+            let preset = prepared_preset(wet_towel);
+            evaporation(&preset.drink, &preset.ambient_ambience)?;
+            model_preference
+                .get()
+                .time_until_temperature(
+                    preset.target_ambience.temperature,
+                    preset.initial_ambience.temperature,
+                    &preset.drink,
+                    &preset.ambient_ambience,
+                )
+                .ok()
+        };
+        Some(time_for(false)? - time_for(true)?)
+    });
+
+    // Ways to move the fluid of the ambience, with their index in `Agitation::ALL`
+    let agitations = Signal::derive(move || {
+        let fluid = preset_signal.get().ambient_ambience.fluid;
+        Agitation::ALL
+            .into_iter()
+            .enumerate()
+            .filter(|(_, agitation)| agitation.applies_to(fluid))
+            .collect::<Vec<_>>()
+    });
 
     let currently_running_timers = expect_context::<CurrentlyRunningTimers>().0;
     let current_time_signal = expect_context::<CurrentTime>().0;
+    let unit_preference = expect_context::<UnitPreference>().0;
     view! {
         <div class="preset_summary">
             <div class="summary">
@@ -38,39 +161,251 @@ pub fn PresetSummary(
                 </div>
                 <div class="summary_sub second">
                     <div class="img_wrapper">
-                        {move || preset_signal.get().initial_ambience.temperature.as_deg_celsius()}
-                        "°C"
+                        {move || {
+                            preset_signal
+                                .get()
+                                .initial_ambience
+                                .temperature
+                                .format(unit_preference.get(), true)
+                        }}
+
                     </div>
                     <span class="description">"Ausgangstemperatur"</span>
                     <span class="info">{move || preset_signal.get().initial_ambience.name}</span>
                 </div>
                 <div class="summary_sub third">
                     <div class="img_wrapper">
-                        {move || preset_signal.get().ambient_ambience.temperature.as_deg_celsius()}
-                        "°C"
+                        {move || {
+                            preset_signal
+                                .get()
+                                .ambient_ambience
+                                .temperature
+                                .format(unit_preference.get(), true)
+                        }}
+
                     </div>
-                    <span class="description">"Kühltemperatur"</span>
-                    <span class="info">{move || preset_signal.get().ambient_ambience.name}</span>
+                    <span class="description">
+                        {move || match preset_signal.get().is_warming() {
+                            true => "Umgebungstemperatur",
+                            false => "Kühltemperatur",
+                        }}
+
+                    </span>
+                    <span class="info">
+                        {move || preset_signal.get().ambient_ambience.name}
+                        {move || {
+                            let agitation = preset_signal.get().ambient_ambience.agitation;
+                            (agitation != Agitation::Still)
+                                .then(|| {
+                                    view! {
+                                        <span class="extra_info">"- " {agitation.get_name()}</span>
+                                    }
+                                })
+                        }}
+
+                    </span>
                 </div>
                 <div class="summary_sub fourth">
                     <div class="img_wrapper">
-                        {move || preset_signal.get().target_ambience.temperature.as_deg_celsius()}
-                        "°C"
+                        {move || {
+                            preset_signal
+                                .get()
+                                .target_ambience
+                                .temperature
+                                .format(unit_preference.get(), true)
+                        }}
+
                     </div>
                     <span class="description">"Zieltemperatur"</span>
-                    <span class="info">{move || preset_signal.get().target_ambience.name}</span>
+                    <span class="info">
+                        {move || preset_signal.get().target_ambience.name}
+                        <span class="extra_info">
+                            "- trinken bei "
+                            {move || {
+                                preset_signal.get().drink.serving_range().format(unit_preference.get())
+                            }}
+
+                        </span>
+                    </span>
                 </div>
             </div>
+            <Show when=move || {
+                agitations.with(|agitations| agitations.iter().any(|(_, a)| *a != Agitation::Still))
+            }
+            fallback=|| view! { "" }>
+                <div class="agitation">
+                    <label>
+                        <span class="description">"Bewegung"</span>
+                        <select on:change=move |ev| {
+                            let agitation = Agitation::ALL
+                                .get(event_target_value(&ev).parse::<usize>().unwrap_or_default())
+                                .copied()
+                                .unwrap_or_default();
+                            preset_signal
+                                .update(|preset| {
+                                    preset.ambient_ambience = preset
+                                        .ambient_ambience
+                                        .clone()
+                                        .with_agitation(agitation);
+                                    preset.rotation_reminder = agitation.reminder_interval();
+                                });
+                        }>
+
+                            {move || {
+                                let selected = preset_signal.get().ambient_ambience.agitation;
+                                agitations
+                                    .get()
+                                    .into_iter()
+                                    .map(|(index, agitation)| {
+                                        view! {
+                                            <option value=index selected=agitation == selected>
+                                                {agitation.get_name()}
+                                            </option>
+                                        }
+                                    })
+                                    .collect_view()
+                            }}
+
+                        </select>
+                    </label>
+                    <label>
+                        <span class="description">"Erinnern alle (min)"</span>
+                        <input
+                            type="number"
+                            min="0"
+                            step="1"
+                            prop:value=move || {
+                                preset_signal.get().rotation_reminder.unwrap_or_default().to_string()
+                            }
+
+                            on:input=move |ev| {
+                                let minutes = event_target_value(&ev).parse::<u32>().unwrap_or_default();
+                                preset_signal
+                                    .update(|preset| {
+                                        preset.rotation_reminder = (minutes > 0).then_some(minutes);
+                                    })
+                            }
+                        />
+
+                    </label>
+                </div>
+            </Show>
+            <Show when=move || wet_towel_saving.get().is_some() fallback=|| view! { "" }>
+                <div class="preparation">
+                    <label class="checkbox">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || wet_towel_signal.get()
+                            on:change=move |ev| {
+                                wet_towel_signal.set(event_target_checked(&ev))
+                            }
+                        />
+
+                        {move || {
+                            format!(
+                                "In nasses Tuch wickeln, spart {} Minuten",
+                                wet_towel_saving.get().unwrap_or_default().num_minutes(),
+                            )
+                        }}
+
+                    </label>
+                    <Show when=move || wet_towel_signal.get() fallback=|| view! { "" }>
+                        <label>
+                            <span class="description">"Luftfeuchtigkeit (%)"</span>
+                            <input
+                                type="number"
+                                min="0"
+                                max="100"
+                                step="5"
+                                prop:value=move || {
+                                    let humidity = preset_signal
+                                        .get()
+                                        .ambient_ambience
+                                        .humidity
+                                        .unwrap_or(DEFAULT_HUMIDITY);
+                                    format!("{:.0}", humidity * 100.0)
+                                }
+
+                                on:input=move |ev| {
+                                    let Ok(humidity) = event_target_value(&ev).parse::<f64>()
+                                    else {
+                                        return;
+                                    };
+                                    preset_signal
+                                        .update(|preset| {
+                                            preset.ambient_ambience = preset
+                                                .ambient_ambience
+                                                .clone()
+                                                .with_humidity(humidity.clamp(0.0, 100.0) / 100.0);
+                                        })
+                                }
+                            />
+
+                        </label>
+                    </Show>
+                </div>
+            </Show>
             <div class="spacer"></div>
             <div class="time_display">
 
-                {move || format_chrono_duration_simple(time_needed.get())}
+                {move || match time_needed_band.get() {
+                    Ok(band) => band.format(),
+                    Err(error) => error.get_description().to_string(),
+                }}
 
             </div>
+            <Show when=move || core_lags.get() fallback=|| view! { "" }>
+                <div class="validity_hint">
+                    {format!(
+                        "Der Kern bleibt länger warm als die Wand, das Modell „{}“ rechnet das mit",
+                        CoolingModelKind::FiniteDifference.get_name(),
+                    )}
+
+                </div>
+            </Show>
+            {move || {
+                salt_tip
+                    .get()
+                    .map(|(salted, salt_mass, saved)| {
+                        view! {
+                            <div class="salt_tip">
+                                <span>
+                                    {format!(
+                                        "Tipp: {:.0} g Salz ins Eisbad sparen {} Minuten",
+                                        salt_mass * 1000.0,
+                                        saved.num_minutes(),
+                                    )}
+
+                                </span>
+                                <button
+                                    class="button"
+                                    on:click=move |_| {
+                                        preset_signal
+                                            .update(|preset| {
+                                                preset.ambient_ambience = salted.clone();
+                                            })
+                                    }
+                                >
+
+                                    "Salz dazu"
+                                </button>
+                            </div>
+                        }
+                    })
+            }}
+
+            <TemperatureChart
+                curve=chart_curve
+                target=Signal::derive(move || preset_signal.get().target_ambience.temperature)
+                freezing_point=Signal::derive(move || preset_signal.get().drink.freezing_point)
+                finished=Signal::derive(move || time_needed.get().ok())
+            />
             <button
                 class="start_timer_button button primary"
+                disabled=move || time_needed.get().is_err()
                 on:click=move |_| {
-                    let timer = TimerInfo::new(preset_signal.get(), current_time_signal);
+                    alarm::prepare();
+                    let timer = TimerInfo::new(calibrated_preset.get(), model_preference.get(), current_time_signal);
                     currently_running_timers.update(move |v| v.push(timer));
                     modal_showing_signal.set(false);
                 }
@@ -0,0 +1,145 @@
+use chrono::Duration;
+use leptos::*;
+
+use crate::{app::UnitPreference, format_chrono_duration_simple, Temperature};
+
+const WIDTH: f64 = 300.;
+const HEIGHT: f64 = 150.;
+/// Room for the axis labels
+const PADDING_LEFT: f64 = 36.;
+const PADDING_BOTTOM: f64 = 16.;
+const PADDING_TOP: f64 = 8.;
+
+/// Evenly spaced points of a temperature curve from zero to the horizon
+pub fn sample_curve(
+    horizon: Duration,
+    samples: usize,
+    temperature_after_time: impl Fn(Duration) -> Temperature,
+) -> Vec<(Duration, Temperature)> {
+    (0..=samples)
+        .map(|sample| {
+            let time = horizon * sample as i32 / samples as i32;
+            (time, temperature_after_time(time))
+        })
+        .collect()
+}
+
+/// Predicted temperature over time as an SVG line chart
+///
+/// All times are relative to the start of the curve.
+#[component]
+pub fn TemperatureChart(
+    #[prop(into)] curve: Signal<Vec<(Duration, Temperature)>>,
+    #[prop(into)] target: MaybeSignal<Temperature>,
+    #[prop(into)] freezing_point: MaybeSignal<Temperature>,
+    #[prop(into)] finished: Signal<Option<Duration>>,
+    /// Marks the current moment, for running timers
+    #[prop(optional, into)]
+    now: Option<Signal<Duration>>,
+) -> impl IntoView {
+    let unit_preference = expect_context::<UnitPreference>().0;
+
+    let horizon = Signal::derive(move || {
+        curve.with(|curve| {
+            curve
+                .last()
+                .map(|(time, _)| time.num_seconds().max(1) as f64)
+                .unwrap_or(1.)
+        })
+    });
+    // Lowest and highest temperature shown, in Kelvin
+    let range = Signal::derive(move || {
+        let marks = [target.get().as_kelvin(), freezing_point.get().as_kelvin()];
+        let (low, high) = curve.with(|curve| {
+            curve
+                .iter()
+                .map(|(_, temperature)| temperature.as_kelvin())
+                .chain(marks)
+                .fold((f64::MAX, f64::MIN), |(low, high), kelvin| {
+                    (low.min(kelvin), high.max(kelvin))
+                })
+        });
+        (low - 1., high + 1.)
+    });
+
+    let x = move |time: Duration| {
+        PADDING_LEFT + time.num_seconds() as f64 / horizon.get() * (WIDTH - PADDING_LEFT)
+    };
+    let y = move |temperature: Temperature| {
+        let (low, high) = range.get();
+        let plot_height = HEIGHT - PADDING_TOP - PADDING_BOTTOM;
+        PADDING_TOP + (high - temperature.as_kelvin()) / (high - low) * plot_height
+    };
+
+    let points = move || {
+        curve
+            .get()
+            .into_iter()
+            .map(|(time, temperature)| format!("{:.1},{:.1}", x(time), y(temperature)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let horizontal_line = move |class: &'static str, temperature: MaybeSignal<Temperature>| {
+        view! {
+            <line
+                class=class
+                x1=PADDING_LEFT
+                x2=WIDTH
+                y1=move || y(temperature.get())
+                y2=move || y(temperature.get())
+            ></line>
+            <text class=class x=PADDING_LEFT - 4. y=move || y(temperature.get()) + 3.>
+                {move || temperature.get().format(unit_preference.get(), true)}
+            </text>
+        }
+    };
+    let vertical_line = move |class: &'static str, time: Duration| {
+        view! {
+            <line
+                class=class
+                x1=x(time)
+                x2=x(time)
+                y1=PADDING_TOP
+                y2=HEIGHT - PADDING_BOTTOM
+            ></line>
+        }
+    };
+
+    view! {
+        <svg
+            class="temperature_chart"
+            viewBox=format!("0 0 {WIDTH} {HEIGHT}")
+            on:click=|ev| ev.stop_propagation()
+        >
+            <line
+                class="axis"
+                x1=PADDING_LEFT
+                x2=PADDING_LEFT
+                y1=PADDING_TOP
+                y2=HEIGHT - PADDING_BOTTOM
+            ></line>
+            <line
+                class="axis"
+                x1=PADDING_LEFT
+                x2=WIDTH
+                y1=HEIGHT - PADDING_BOTTOM
+                y2=HEIGHT - PADDING_BOTTOM
+            ></line>
+            {horizontal_line("freezing_point", freezing_point)}
+            {horizontal_line("target", target)}
+            {move || finished.get().map(|finished| vertical_line("finished", finished))}
+            {move || now.map(|now| vertical_line("now", now.get()))}
+            <polyline class="curve" points=points></polyline>
+            <text class="time_label" x=PADDING_LEFT y=HEIGHT - 2.>
+                "0:00"
+            </text>
+            <text class="time_label end" x=WIDTH y=HEIGHT - 2.>
+                {move || {
+                    format_chrono_duration_simple(Duration::seconds(horizon.get() as i64))
+                }}
+
+            </text>
+        </svg>
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{
+    app::{Calibrations, CurrentTime, KnownAmbiences, UnitPreference},
+    timer_info::TimerInfo,
+};
+use leptos::*;
+
+#[component]
+pub fn TimerControls(timer: TimerInfo) -> impl IntoView {
+    let ambiences = expect_context::<KnownAmbiences>();
+    let calibrations = expect_context::<Calibrations>().0;
+    let current_time_signal = expect_context::<CurrentTime>().0;
+    let unit_preference = expect_context::<UnitPreference>().0;
+
+    let current_ambience = timer.current_ambience;
+    let paused = timer.paused;
+    let pause_ambience = ambiences.pause.clone();
+    let timer = store_value(timer);
+
+    let toggle_pause = move |_| {
+        let now = current_time_signal.get();
+        calibrations.with(|calibrations| {
+            timer.with_value(|timer| match paused.get() {
+                true => timer.resume(now, calibrations),
+                false => timer.move_to(pause_ambience.clone(), true, now, calibrations),
+            })
+        });
+    };
+
+    let ambient = store_value(ambiences.ambient);
+    let move_to = move |ev| {
+        let Ok(index) = event_target_value(&ev).parse::<usize>() else {
+            return;
+        };
+        let ambience = ambient.with_value(|ambient| ambient[index].clone());
+        if ambience.id == current_ambience.get().id {
+            return;
+        }
+        let now = current_time_signal.get();
+        calibrations.with(|calibrations| {
+            timer.with_value(|timer| timer.move_to(ambience, false, now, calibrations))
+        });
+    };
+
+    view! {
+        <div class="timer_controls" on:click=|ev| ev.stop_propagation()>
+            <button class="button" on:click=toggle_pause>
+                {move || match paused.get() {
+                    true => "Fortsetzen",
+                    false => "Pausieren",
+                }}
+
+            </button>
+            <label>
+                <span class="description">"Verschieben nach"</span>
+                <select on:change=move_to>
+                    <option value="" disabled=true selected=move || paused.get()>
+                        {move || current_ambience.get().name}
+                    </option>
+
+                    {ambient
+                        .get_value()
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, ambience)| {
+                            let id = ambience.id;
+                            view! {
+                                <option
+                                    value=index
+                                    selected=move || {
+                                        !paused.get() && current_ambience.get().id == id
+                                    }
+                                >
+
+                                    {move || {
+                                        format!(
+                                            "{} ({})",
+                                            ambience.name,
+                                            ambience.temperature.format(unit_preference.get(), true),
+                                        )
+                                    }}
+
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+            </label>
+        </div>
+    }
+}
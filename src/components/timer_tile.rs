@@ -5,14 +5,29 @@ use leptos_icons::{
     VsIcon::VsCircleLargeFilled,
 };
 
+use chrono::Duration;
+
 use crate::{
-    app::CurrentlyRunningTimers, format_chrono_duration_precise, linear_interpolate_ceil,
-    timer_info::TimerInfo,
+    app::{CurrentTime, CurrentlyRunningTimers, UnitPreference},
+    components::{sample_curve, CalibrationForm, TemperatureChart, TimerControls},
+    format_chrono_duration_precise, linear_interpolate_ceil,
+    model::CoolingModelKind,
+    segment,
+    serving::ServingBand,
+    timer_info::{TimerInfo, TimerState},
+    validity::validity,
+    Phase,
 };
 
 #[component]
 pub fn TimerTile(timer: TimerInfo) -> impl IntoView {
     let currently_running_timers = expect_context::<CurrentlyRunningTimers>().0;
+    let unit_preference = expect_context::<UnitPreference>().0;
+    // Share of the cold color, a warming drink starts cold and ends hot
+    let (cold_at_start, cold_at_target) = match timer.is_warming() {
+        true => (100.0, 0.0),
+        false => (0.0, 100.0),
+    };
     let circle_color = create_memo(move |_| {
         format!(
             "color-mix(in srgb, var(--color-hot), var(--color-cold) {}%)",
@@ -20,18 +35,87 @@ pub fn TimerTile(timer: TimerInfo) -> impl IntoView {
                 timer.current_temperature.get().as_deg_celsius(),
                 timer.initial_ambience.temperature.as_deg_celsius(),
                 timer.target_ambience.temperature.as_deg_celsius(),
-                0.0,
-                100.0,
+                cold_at_start,
+                cold_at_target,
             )
             .round(),
         )
     });
     let timer_tile_expanded = create_rw_signal(false);
+    let serving_range = timer.drink.serving_range();
+    let serving_band = create_memo(move |_| serving_range.band(timer.current_temperature.get()));
+
+    let current_time_signal = expect_context::<CurrentTime>().0;
+    let start = timer.timestamp_started;
+    let chart_now = Signal::derive(move || current_time_signal.get() - start);
+    let chart_finished = Signal::derive(move || {
+        timer
+            .timestamp_finished
+            .get()
+            .map(|finished| finished - start)
+    });
+    let chart_drink = store_value(timer.drink.clone());
+    let initial_temperature = timer.initial_ambience.temperature;
+    let chart_curve = Signal::derive(move || {
+        // Whole timer plus some of the time after it, or the next
+        // hour if the target is out of reach
+        let end = chart_finished
+            .get()
+            .unwrap_or(chart_now.get() + Duration::hours(1))
+            .max(chart_now.get());
+        let horizon = (end * 6 / 5).max(Duration::minutes(10));
+
+        let batch = timer.batch.get();
+        timer.course.with(|course| {
+            timer.segments.with(|segments| {
+                chart_drink.with_value(|drink| {
+                    sample_curve(horizon, 60, |time| {
+                        batch
+                            .as_ref()
+                            .and_then(|prediction| prediction.temperature_at(start + time))
+                            .or_else(|| course.as_ref()?.temperature_at(start + time))
+                            .unwrap_or_else(|| {
+                                segment::temperature_at(
+                                    start + time,
+                                    segments,
+                                    initial_temperature,
+                                    drink,
+                                    &timer.model,
+                                )
+                                .unwrap_or(initial_temperature)
+                            })
+                    })
+                })
+            })
+        })
+    });
+    // Valid for the temperature the drink entered its current ambience with
+    let current_validity = Signal::derive(move || {
+        let start_temperature = timer
+            .course
+            .with(|course| course.as_ref()?.current_start_temperature())?;
+        timer.segments.with(|segments| {
+            let segment = segments.last()?;
+            chart_drink
+                .with_value(|drink| {
+                    validity(start_temperature, &segment.drink(drink), &segment.ambience)
+                })
+                .ok()
+        })
+    });
+    let chart_target = timer.target_ambience.temperature;
+    let chart_freezing_point = timer.drink.freezing_point;
+
+    let calibration_timer = timer.clone();
+    let controls_timer = timer.clone();
 
     view! {
-        <button
+        <div
             class="timer_tile"
             class:expanded=move || timer_tile_expanded.get()
+            class:overdue=move || matches!(timer.current_state.get(), TimerState::Overdue(_))
+            class:critical=move || timer.current_state.get().is_critical()
+            class:frozen=move || timer.fully_frozen.get()
             on:click=move |_ev| {
                 timer_tile_expanded
                     .set(
@@ -64,7 +148,7 @@ pub fn TimerTile(timer: TimerInfo) -> impl IntoView {
                 // style=
 
                 {create_memo(move |_| {
-                    timer.current_temperature.get().format(crate::TemperatureUnit::DegCelsius, true)
+                    timer.current_temperature.get().format(unit_preference.get(), true)
                 })}
 
                 {move || {
@@ -74,20 +158,32 @@ pub fn TimerTile(timer: TimerInfo) -> impl IntoView {
                         Some(
                             view! {
                                 <span class="arrow">" -> "</span>
-                                {timer
-                                    .target_ambience
-                                    .temperature
-                                    .format(crate::TemperatureUnit::DegCelsius, true)}
+                                {timer.target_ambience.temperature.format(unit_preference.get(), true)}
                             },
                         )
                     }
                 }}
 
+                <span
+                    class="serving_band"
+                    class:ideal=move || serving_band.get() == ServingBand::Ideal
+                    title=move || serving_range.format(unit_preference.get())
+                >
+                    {move || serving_band.get().get_name()}
+                </span>
             </div>
             <div class="time_display">
-                {create_memo(move |_| format_chrono_duration_precise(
-                    timer.current_time_left.get(),
-                ))}
+                {create_memo(move |_| {
+                    let now = current_time_signal.get();
+                    match (timer.finished_band.get(), timer.current_time_left.get()) {
+                        (Some(band), _) if band.pessimistic > now => {
+                            band.map(|time| time - now).format()
+                        }
+                        (_, Some(time_left)) => format_chrono_duration_precise(time_left),
+                        (_, None) if timer.paused.get() => "Pausiert".to_string(),
+                        (_, None) => "--:--".to_string(),
+                    }
+                })}
 
             </div>
             <div class="controls">
@@ -118,12 +214,133 @@ pub fn TimerTile(timer: TimerInfo) -> impl IntoView {
                     </p>
 
                     <p>
-                        "Timer finishes: "
-                        {move || timer.timestamp_finished.naive_local().format("%H:%M").to_string()}
+                        "Timer fertig: "
+                        {move || match timer.finished_band.get() {
+                            Some(band) => {
+                                format!(
+                                    "{} ({} - {})",
+                                    band.expected.naive_local().format("%H:%M"),
+                                    band.optimistic.naive_local().format("%H:%M"),
+                                    band.pessimistic.naive_local().format("%H:%M"),
+                                )
+                            }
+                            None => "nie".to_string(),
+                        }}
+
+                    </p>
+
+                    {move || {
+                        current_validity
+                            .get()
+                            .map(|validity| {
+                                let fit = match (validity.is_lumped(), timer.model) {
+                                    (true, _) => "das Getränk hat überall dieselbe Temperatur",
+                                    (false, CoolingModelKind::Exponential) => {
+                                        "der Kern hinkt der Wand hinterher, die Zeiten sind grob"
+                                    }
+                                    (false, _) => "der Kern hinkt der Wand hinterher",
+                                };
+                                view! {
+                                    <p>
+                                        {format!(
+                                            "Biot-Zahl: {:.2}, {fit}",
+                                            validity.biot_number,
+                                        )}
+                                    </p>
+                                }
+                            })
+                    }}
+
+                    <p>
+                        "Freeze deadline: "
+                        {move || match timer.timestamp_freezing.get() {
+                            Some(freezing) => freezing.naive_local().format("%H:%M").to_string(),
+                            None => "none".to_string(),
+                        }}
+
                     </p>
+
+                    {move || {
+                        timer
+                            .timestamp_frozen
+                            .get()
+                            .map(|frozen| {
+                                view! {
+                                    <p>
+                                        "Frozen solid: "
+                                        {frozen.naive_local().format("%H:%M").to_string()}
+                                    </p>
+                                }
+                            })
+                    }}
+
+                    <p>
+                        "Currently in: " {move || timer.current_ambience.get().name}
+                    </p>
+
+                    {move || {
+                        timer
+                            .batch
+                            .get()
+                            .map(|_| {
+                                view! { <p>"Shares the space with other drinks, cooling is slower"</p> }
+                            })
+                    }}
+
+                    <TemperatureChart
+                        curve=chart_curve
+                        target=chart_target
+                        freezing_point=chart_freezing_point
+                        finished=chart_finished
+                        now=chart_now
+                    />
+
+                    <TimerControls timer=controls_timer.clone()/>
+
+                    <CalibrationForm timer=calibration_timer.clone()/>
                 </div>
             </Show>
-        </button>
+
+            <Show
+                when=move || {
+                    !matches!(timer.current_state.get(), TimerState::Running | TimerState::Done)
+                }
+
+                fallback=|| view! { "" }
+            >
+                <div class="freeze_warning">
+                    {move || match timer.current_state.get() {
+                        TimerState::Overdue(_) => {
+                            format!(
+                                "Raus damit! Gefriert in {}",
+                                format_chrono_duration_precise(
+                                    timer.current_time_until_freezing.get().unwrap_or_default(),
+                                ),
+                            )
+                        }
+                        TimerState::Freezing => {
+                            match timer.current_phase.get() {
+                                Phase::Freezing(fraction) => {
+                                    format!("Gefriert ({:.0} % Eis)", fraction * 100.0)
+                                }
+                                _ => "Gefriert".to_string(),
+                            }
+                        }
+                        TimerState::Frozen => "Gefroren - Flasche kann platzen!".to_string(),
+                        TimerState::Running | TimerState::Done => String::new(),
+                    }}
+
+                </div>
+            </Show>
+
+            {move || {
+                timer
+                    .error
+                    .get()
+                    .map(|error| view! { <div class="freeze_warning">{error.get_description()}</div> })
+            }}
+
+        </div>
     }
 }
 
@@ -0,0 +1,29 @@
+use crate::{app::UnitPreference, helpers::TemperatureUnit};
+use leptos::*;
+
+#[component]
+pub fn UnitSwitch() -> impl IntoView {
+    let unit_preference = expect_context::<UnitPreference>().0;
+
+    view! {
+        <div class="unit_switch">
+
+            {TemperatureUnit::DISPLAY_UNITS
+                .iter()
+                .map(|unit| {
+                    let unit = *unit;
+                    view! {
+                        <button
+                            class="button"
+                            class:primary=move || unit_preference.get() == unit
+                            on:click=move |_| unit_preference.set(unit)
+                        >
+                            {unit.get_symbol()}
+                        </button>
+                    }
+                })
+                .collect_view()}
+
+        </div>
+    }
+}
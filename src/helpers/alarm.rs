@@ -0,0 +1,118 @@
+use std::cell::RefCell;
+
+use chrono::{DateTime, Duration, Local};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+use web_sys::{AudioContext, Notification, NotificationOptions, NotificationPermission};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+/// Alarm that goes off once when a timer finishes
+pub enum AlarmState {
+    /// Timer is still running
+    #[default]
+    Pending,
+    Ringing,
+    /// Rings again at the given time
+    Snoozed(DateTime<Local>),
+    Dismissed,
+}
+
+/// How long a snoozed alarm stays quiet
+pub fn snooze_duration() -> Duration {
+    Duration::minutes(5)
+}
+
+thread_local! {
+    static AUDIO_CONTEXT: RefCell<Option<AudioContext>> = const { RefCell::new(None) };
+}
+
+/// Asks for notification permission and unlocks audio playback.
+///
+/// Browsers only allow both after a user interaction, so this has
+/// to be called from a click handler.
+pub fn prepare() {
+    if notifications_supported() && Notification::permission() == NotificationPermission::Default {
+        _ = Notification::request_permission();
+    }
+
+    AUDIO_CONTEXT.with(|context| {
+        let mut context = context.borrow_mut();
+        if context.is_none() {
+            *context = AudioContext::new().ok();
+        }
+        if let Some(context) = context.as_ref() {
+            _ = context.resume();
+        }
+    });
+}
+
+/// Whether the browser knows the Notification API at all,
+/// e.g. iOS Safari outside of installed web apps does not
+pub fn notifications_supported() -> bool {
+    js_sys::Reflect::has(&window(), &JsValue::from_str("Notification")).unwrap_or(false)
+}
+
+pub fn notifications_permitted() -> bool {
+    notifications_supported() && Notification::permission() == NotificationPermission::Granted
+}
+
+/// The user blocked notifications, the browser won't ask again,
+/// only its settings for the site can allow them
+pub fn notifications_denied() -> bool {
+    notifications_supported() && Notification::permission() == NotificationPermission::Denied
+}
+
+/// Sends a notification, if permitted, and plays the alarm tone.
+///
+/// Notifications with the same tag replace each other.
+pub fn ring(title: &str, body: &str, tag: &str) {
+    if notifications_permitted() {
+        let mut options = NotificationOptions::new();
+        options
+            .body(body)
+            .tag(tag)
+            .renotify(true)
+            .require_interaction(true);
+        if Notification::new_with_options(title, &options).is_err() {
+            logging::warn!("could not show notification");
+        }
+    }
+
+    if play_tone().is_err() {
+        logging::warn!("could not play alarm tone");
+    }
+}
+
+/// Three short beeps, generated so no audio file has to be loaded
+fn play_tone() -> Result<(), JsValue> {
+    AUDIO_CONTEXT.with(|context| {
+        let context = context.borrow();
+        let Some(context) = context.as_ref() else {
+            return Ok(());
+        };
+
+        let now = context.current_time();
+        for beep in 0..3 {
+            let start = now + beep as f64 * 0.4;
+            let end = start + 0.3;
+
+            let oscillator = context.create_oscillator()?;
+            oscillator.frequency().set_value(880.0);
+
+            // Short fade in and out to avoid clicks
+            let gain = context.create_gain()?;
+            let volume = gain.gain();
+            volume.set_value_at_time(0.0, start)?;
+            volume.linear_ramp_to_value_at_time(0.3, start + 0.02)?;
+            volume.linear_ramp_to_value_at_time(0.0, end)?;
+
+            oscillator.connect_with_audio_node(&gain)?;
+            gain.connect_with_audio_node(&context.destination())?;
+            oscillator.start_with_when(start)?;
+            oscillator.stop_with_when(end)?;
+        }
+
+        Ok(())
+    })
+}
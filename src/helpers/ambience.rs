@@ -1,8 +1,9 @@
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::{Fluid, Temperature};
+use super::{drink::HeatTransferCoefficient, ice_bath::IceBath, Fluid, Temperature};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// Can be a fridge, freezer, or others
 pub struct Ambience {
     pub id: Uuid,
@@ -10,6 +11,109 @@ pub struct Ambience {
     pub path_to_image: String,
     pub temperature: Temperature,
     pub fluid: Fluid,
+    /// Limited cooling of an appliance, `None` if the ambience
+    /// keeps its temperature no matter how many drinks are in it
+    #[serde(default)]
+    pub capacity: Option<CoolingCapacity>,
+    /// Recipe if the ambience is an ice bath, to suggest adding salt
+    #[serde(default)]
+    pub ice_bath: Option<IceBath>,
+    #[serde(default)]
+    pub agitation: Agitation,
+    /// Relative humidity of the air between 0. and 1., `None` for
+    /// [`crate::helpers::evaporation::DEFAULT_HUMIDITY`], only matters for drinks wrapped wet
+    #[serde(default)]
+    pub humidity: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+/// How much the fluid around the drink is moved, moving fluid
+/// carries heat away far better than fluid warmed up at the glass
+pub enum Agitation {
+    #[default]
+    Still,
+    /// Stirred or shaken every few minutes
+    Stirred,
+    /// Bottle kept turning in the bath
+    Rotated,
+    /// Fan blowing air at the drink
+    Fan,
+}
+
+impl Agitation {
+    pub const ALL: [Agitation; 4] = [Self::Still, Self::Stirred, Self::Rotated, Self::Fan];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Still => "Ruhend",
+            Self::Stirred => "Ab und zu umrühren",
+            Self::Rotated => "Flasche drehen",
+            Self::Fan => "Ventilator",
+        }
+    }
+
+    /// Whether the agitation makes sense in the fluid, stirring air
+    /// or fanning water does nothing
+    pub fn applies_to(&self, fluid: Fluid) -> bool {
+        match self {
+            Self::Still => true,
+            Self::Stirred | Self::Rotated => {
+                matches!(fluid, Fluid::Water | Fluid::Brine { .. } | Fluid::Ethanol)
+            }
+            Self::Fan => fluid == Fluid::Air,
+        }
+    }
+
+    /// Factor on the heat transfer coefficient of the still fluid,
+    /// rough values from timing bottles in ice water and freezers
+    pub fn heat_transfer_factor(&self, fluid: Fluid) -> f64 {
+        if !self.applies_to(fluid) {
+            return 1.;
+        }
+        match self {
+            Self::Still => 1.,
+            Self::Stirred => 1.5,
+            Self::Rotated => 3.,
+            // Same as the forced air of a fan freezer
+            Self::Fan => {
+                Fluid::ForcedAir.get_heat_transfer_coefficient()
+                    / Fluid::Air.get_heat_transfer_coefficient()
+            }
+        }
+    }
+
+    /// Minutes between two reminders to move the bottle, `None` if
+    /// there is nothing to do by hand
+    pub fn reminder_interval(&self) -> Option<u32> {
+        match self {
+            Self::Still | Self::Fan => None,
+            Self::Stirred => Some(5),
+            Self::Rotated => Some(2),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Cooling space of an appliance that warms up when filled with
+/// warm drinks, see [`crate::helpers::batch`]
+pub struct CoolingCapacity {
+    /// Heat capacity of the air, walls and shelves in J / K
+    pub heat_capacity: f64,
+    /// Maximum heat the compressor removes in W
+    pub compressor_power: f64,
+}
+
+impl CoolingCapacity {
+    /// Freezer compartment of a household fridge
+    pub const FREEZER: CoolingCapacity = CoolingCapacity {
+        heat_capacity: 20_000.,
+        compressor_power: 100.,
+    };
+    /// Household fridge
+    pub const FRIDGE: CoolingCapacity = CoolingCapacity {
+        heat_capacity: 25_000.,
+        compressor_power: 80.,
+    };
 }
 
 impl Ambience {
@@ -19,12 +123,102 @@ impl Ambience {
         temperature: Temperature,
         fluid: Option<Fluid>,
     ) -> Self {
-        Ambience {
-            id: Uuid::new_v4(),
+        let mut ambience = Ambience {
+            id: Uuid::nil(),
             name: String::from(name),
             path_to_image: String::from(path_to_image),
             temperature,
             fluid: fluid.unwrap_or(Fluid::Air),
-        }
+            capacity: None,
+            ice_bath: None,
+            agitation: Agitation::Still,
+            humidity: None,
+        };
+        ambience.id = ambience.content_id();
+        ambience
+    }
+
+    /// Derived from the content, so stored presets still match
+    /// the built-in ambiences after a reload
+    pub(crate) fn content_id(&self) -> Uuid {
+        let key = format!(
+            "{}/{}/{:?}/{:?}/{:?}",
+            self.name,
+            self.temperature.as_kelvin(),
+            self.fluid,
+            self.agitation,
+            self.humidity
+        );
+        Uuid::new_v5(&Uuid::NAMESPACE_OID, key.as_bytes())
+    }
+
+    pub fn with_capacity(mut self, capacity: CoolingCapacity) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Ambience with the fluid moved, gets an id of its own so
+    /// calibrations of the still ambience don't apply
+    pub fn with_agitation(mut self, agitation: Agitation) -> Self {
+        self.agitation = agitation;
+        self.id = self.content_id();
+        self
+    }
+
+    /// Ambience with known humidity, gets an id of its own so
+    /// calibrations of wet-wrapped drinks at another humidity don't
+    /// apply
+    pub fn with_humidity(mut self, humidity: f64) -> Self {
+        self.humidity = Some(humidity);
+        self.id = self.content_id();
+        self
+    }
+
+    /// Heat transfer coefficient between the fluid and the outside
+    /// of the container in W / (m^2 * K)
+    pub fn heat_transfer_coefficient(&self) -> f64 {
+        self.fluid.get_heat_transfer_coefficient() * self.agitation.heat_transfer_factor(self.fluid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::catalog;
+
+    #[test]
+    fn agitation_only_helps_in_fluids_it_applies_to() {
+        let bath = catalog::ice_bath();
+        let rotated = bath.clone().with_agitation(Agitation::Rotated);
+        assert_eq!(
+            rotated.heat_transfer_coefficient(),
+            3. * bath.heat_transfer_coefficient()
+        );
+
+        let fridge = catalog::fridge();
+        let stirred = fridge.clone().with_agitation(Agitation::Stirred);
+        assert_eq!(
+            stirred.heat_transfer_coefficient(),
+            fridge.heat_transfer_coefficient()
+        );
+    }
+
+    #[test]
+    fn fan_matches_a_fan_freezer() {
+        let fanned = catalog::freezer().with_agitation(Agitation::Fan);
+        assert_eq!(
+            fanned.heat_transfer_coefficient(),
+            Fluid::ForcedAir.get_heat_transfer_coefficient()
+        );
+    }
+
+    #[test]
+    fn agitation_changes_the_id_but_capacity_does_not() {
+        let bath = catalog::ice_bath();
+        assert_ne!(bath.id, bath.clone().with_agitation(Agitation::Stirred).id);
+        assert_eq!(
+            bath.id,
+            bath.clone().with_capacity(CoolingCapacity::FRIDGE).id
+        );
     }
 }
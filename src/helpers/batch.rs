@@ -0,0 +1,301 @@
+//! Many drinks sharing one cooling space
+//!
+//! A single bottle barely warms up a freezer, so the exponential
+//! model assumes a constant ambient temperature. A whole crate
+//! does: the air, walls and shelves are a finite thermal mass and
+//! the compressor can only remove so much heat. This steps the
+//! temperatures of all drinks and the shared air forward in time:
+//!
+//! - every drink loses `k * C * (T - T_sink)` W to the air, like
+//!   in the exponential model, its sink temperature rises with the
+//!   air as the air warms up
+//! - the compressor removes up to its maximum power, ramping up
+//!   to full power at [`THERMOSTAT_BAND`] above the set temperature
+//!
+//! Each drink is tracked by its enthalpy, so a drink that reaches
+//! its freezing point stays there until its latent heat is gone,
+//! like in the exponential model. The drinks are always treated as
+//! lumps, whichever model the timers use.
+
+use chrono::{DateTime, Duration, Local};
+use uuid::Uuid;
+
+use super::{
+    ambience::CoolingCapacity, cooling_coefficient, sink_temperature, Ambience, CoolingError,
+    Drink, Temperature,
+};
+
+/// Degrees above the set temperature at which the compressor runs
+/// at full power, in K
+const THERMOSTAT_BAND: f64 = 1.;
+/// Integration step in s
+const STEP: f64 = 10.;
+/// Time between two points of the predicted curves
+const SAMPLE_INTERVAL: Duration = Duration::minutes(1);
+/// How long to follow the drinks after the last one was put in
+const HORIZON: Duration = Duration::hours(12);
+
+/// Drink put into a shared cooling space
+pub struct BatchEntry<'a> {
+    pub id: Uuid,
+    pub drink: &'a Drink,
+    pub entered: DateTime<Local>,
+    pub start_temperature: Temperature,
+    pub target_temperature: Temperature,
+}
+
+#[derive(Debug, Clone)]
+/// Predicted course of one drink in a shared cooling space
+pub struct BatchPrediction {
+    pub id: Uuid,
+    /// When the drink was put in, to tell predictions of earlier
+    /// stays in the same ambience apart
+    pub entered: DateTime<Local>,
+    /// Temperature of the drink once per minute from `entered` on
+    pub curve: Vec<(DateTime<Local>, Temperature)>,
+    /// When the target temperature is reached, `None` if not
+    /// within the simulated time
+    pub finished: Option<DateTime<Local>>,
+}
+
+impl BatchPrediction {
+    /// Temperature at the given time, linearly interpolated
+    /// between the points of the curve
+    ///
+    /// Returns `None` outside of the simulated time.
+    pub fn temperature_at(&self, time: DateTime<Local>) -> Option<Temperature> {
+        let after = self.curve.iter().position(|(sample, _)| *sample >= time)?;
+        let (time_after, temperature_after) = self.curve[after];
+        if after == 0 {
+            return (time_after == time).then_some(temperature_after);
+        }
+
+        let (time_before, temperature_before) = self.curve[after - 1];
+        let fraction = (time - time_before).num_milliseconds() as f64
+            / (time_after - time_before).num_milliseconds() as f64;
+        Some(Temperature::new(
+            temperature_before.as_kelvin()
+                + (temperature_after.as_kelvin() - temperature_before.as_kelvin()) * fraction,
+        ))
+    }
+}
+
+/// Simulates all drinks in an ambience of limited capacity together
+pub fn simulate(
+    ambience: &Ambience,
+    capacity: CoolingCapacity,
+    entries: &[BatchEntry],
+) -> Result<Vec<BatchPrediction>, CoolingError> {
+    let Some(first_entered) = entries.iter().map(|entry| entry.entered).min() else {
+        return Ok(vec![]);
+    };
+    let last_entered = entries
+        .iter()
+        .map(|entry| entry.entered)
+        .max()
+        .unwrap_or(first_entered);
+    let end = last_entered + HORIZON;
+
+    let set_temperature = ambience.temperature.as_kelvin();
+    let mut air_temperature = set_temperature;
+    // Enthalpy in J of the drinks that are already in
+    let mut enthalpies: Vec<Option<f64>> = vec![None; entries.len()];
+    let mut predictions: Vec<BatchPrediction> = entries
+        .iter()
+        .map(|entry| BatchPrediction {
+            id: entry.id,
+            entered: entry.entered,
+            curve: vec![],
+            finished: None,
+        })
+        .collect();
+    let cooling_coefficients = entries
+        .iter()
+        .map(|entry| cooling_coefficient(entry.drink, ambience))
+        .collect::<Result<Vec<f64>, _>>()?;
+    // Below the air by the cooling of evaporation, in K
+    let sink_offsets = entries
+        .iter()
+        .map(|entry| sink_temperature(entry.drink, ambience).as_kelvin() - set_temperature)
+        .collect::<Vec<f64>>();
+
+    let step = Duration::milliseconds((STEP * 1000.) as i64);
+    let mut time = first_entered;
+    let mut next_sample = first_entered;
+    while time <= end {
+        let sample = time >= next_sample;
+        if sample {
+            next_sample += SAMPLE_INTERVAL;
+        }
+
+        let mut heat_flow = 0.;
+        for (index, entry) in entries.iter().enumerate() {
+            let enthalpy = match enthalpies[index] {
+                Some(enthalpy) => enthalpy,
+                None if time >= entry.entered => enthalpy(entry.drink, entry.start_temperature),
+                None => continue,
+            };
+            let temperature = temperature(entry.drink, enthalpy).as_kelvin();
+
+            let prediction = &mut predictions[index];
+            if sample || prediction.curve.is_empty() {
+                prediction.curve.push((time, Temperature::new(temperature)));
+            }
+            let target = entry.target_temperature.as_kelvin();
+            let cooling = entry.start_temperature.as_kelvin() > target;
+            let reached = match cooling {
+                true => temperature <= target,
+                false => temperature >= target,
+            };
+            if prediction.finished.is_none() && reached {
+                prediction.finished = Some(time);
+            }
+
+            // The conductance stays the same once the drink is
+            // frozen, see `frozen_cooling_coefficient`. Water
+            // evaporated from a wet towel freezes out in the cooling
+            // space, so all of the heat ends up warming it.
+            let drink_heat_flow = cooling_coefficients[index]
+                * entry.drink.heat_capacity
+                * (temperature - air_temperature - sink_offsets[index]);
+            heat_flow += drink_heat_flow;
+            enthalpies[index] = Some(enthalpy - drink_heat_flow * STEP);
+        }
+
+        let compressor = (capacity.compressor_power * (air_temperature - set_temperature)
+            / THERMOSTAT_BAND)
+            .clamp(0., capacity.compressor_power);
+        air_temperature += (heat_flow - compressor) / capacity.heat_capacity * STEP;
+
+        time += step;
+    }
+
+    Ok(predictions)
+}
+
+/// Enthalpy of the drink in J, zero is liquid at the freezing point,
+/// a drink below its freezing point is all ice
+fn enthalpy(drink: &Drink, temperature: Temperature) -> f64 {
+    match (temperature - drink.freezing_point).as_kelvin() {
+        difference if difference < 0. => {
+            drink.heat_capacity_frozen * difference - drink.latent_heat
+        }
+        difference => drink.heat_capacity * difference,
+    }
+}
+
+/// Temperature of the drink with the given enthalpy, see [`enthalpy`]
+fn temperature(drink: &Drink, enthalpy: f64) -> Temperature {
+    let freezing_point = drink.freezing_point.as_kelvin();
+    Temperature::new(if enthalpy >= 0. {
+        freezing_point + enthalpy / drink.heat_capacity
+    } else if enthalpy >= -drink.latent_heat {
+        freezing_point
+    } else {
+        freezing_point + (enthalpy + drink.latent_heat) / drink.heat_capacity_frozen
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        catalog,
+        drink::{Layer, LayerMaterial},
+        fixtures::{assert_close, start},
+        temperature_after_time,
+    };
+
+    fn entry(drink: &Drink, index: u128) -> BatchEntry<'_> {
+        BatchEntry {
+            id: Uuid::from_u128(index),
+            drink,
+            entered: start(),
+            start_temperature: catalog::room_temperature().temperature,
+            target_temperature: catalog::target_beer().temperature,
+        }
+    }
+
+    /// Cooling space that no drink warms up noticeably
+    const UNLIMITED: CoolingCapacity = CoolingCapacity {
+        heat_capacity: 1e12,
+        compressor_power: 1e9,
+    };
+
+    /// Largest difference in K to the single drink model over the horizon
+    fn deviation_from_single_drink(drink: &Drink, ambience: &Ambience) -> f64 {
+        let predictions = simulate(ambience, UNLIMITED, &[entry(drink, 1)]).unwrap();
+        (0..12 * 4)
+            .map(|quarter| {
+                let time = Duration::minutes(15 * quarter);
+                let single = temperature_after_time(
+                    time,
+                    catalog::room_temperature().temperature,
+                    drink,
+                    ambience,
+                )
+                .unwrap();
+                let batch = predictions[0].temperature_at(start() + time).unwrap();
+                (batch.as_kelvin() - single.as_kelvin()).abs()
+            })
+            .fold(0., f64::max)
+    }
+
+    #[test]
+    fn single_drink_matches_the_exponential_model() {
+        let deviation =
+            deviation_from_single_drink(&catalog::beer_500_glass(), &catalog::freezer());
+        assert!(deviation < 0.2, "{deviation} K");
+    }
+
+    #[test]
+    fn wet_towel_cools_towards_the_sink_temperature() {
+        let drink = catalog::beer_500_can().with_layer(Layer::new(LayerMaterial::WetTowel));
+        let deviation = deviation_from_single_drink(&drink, &catalog::fridge());
+        assert!(deviation < 0.2, "{deviation} K");
+    }
+
+    #[test]
+    fn drinks_in_a_freezer_stay_at_their_freezing_point() {
+        let drink = catalog::beer_500_glass();
+        let predictions = simulate(
+            &catalog::freezer(),
+            CoolingCapacity::FREEZER,
+            &[entry(&drink, 1), entry(&drink, 2)],
+        )
+        .unwrap();
+
+        for hours in [2, 6] {
+            let temperature = predictions[0]
+                .temperature_at(start() + Duration::hours(hours))
+                .unwrap();
+            assert_close(
+                temperature.as_kelvin(),
+                drink.freezing_point.as_kelvin(),
+                1e-6,
+            );
+        }
+    }
+
+    #[test]
+    fn shared_freezer_slows_the_drinks_down() {
+        let drink = catalog::beer_500_glass();
+        let alone = simulate(
+            &catalog::freezer(),
+            CoolingCapacity::FREEZER,
+            &[entry(&drink, 1)],
+        )
+        .unwrap();
+        let crate_of_beer = (0..20)
+            .map(|index| entry(&drink, index))
+            .collect::<Vec<_>>();
+        let shared = simulate(
+            &catalog::freezer(),
+            CoolingCapacity::FREEZER,
+            &crate_of_beer,
+        )
+        .unwrap();
+
+        assert!(shared[0].finished.unwrap() > alone[0].finished.unwrap());
+    }
+}
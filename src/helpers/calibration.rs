@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{sink_temperature, Ambience, Drink, Temperature};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+/// Temperature of a drink measured by the user
+pub struct Measurement {
+    /// Seconds since the drink was put into the ambience
+    pub seconds: f64,
+    pub temperature: Temperature,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Cooling coefficient fitted to measurements of a drink in
+/// a specific ambience, replaces the calculated one
+pub struct Calibration {
+    pub drink_id: Uuid,
+    pub ambience_id: Uuid,
+    /// Cooling coefficient in 1 / s
+    pub cooling_coefficient: f64,
+}
+
+impl Drink {
+    /// Copy of the drink with the cooling coefficient in the ambience
+    /// replaced by a matching calibration
+    pub fn calibrated(&self, ambience: &Ambience, calibrations: &[Calibration]) -> Drink {
+        let mut drink = self.clone();
+        let calibration = calibrations
+            .iter()
+            .find(|c| c.drink_id == self.id && c.ambience_id == ambience.id);
+
+        if let Some(calibration) = calibration {
+            drink.calibration = Some((ambience.id, calibration.cooling_coefficient));
+        }
+
+        drink
+    }
+}
+
+/// Least squares fit of the cooling coefficient to measured
+/// temperatures, using the linearized exponential model
+/// `ln((T - T_s) / (T_0 - T_s)) = -k * t` with the sink temperature
+/// `T_s` the drink tends to, below the air for a wet-wrapped drink
+///
+/// Measurements at or below the freezing point are ignored, as
+/// the exponential model does not hold there. Returns `None` if
+/// no usable measurement is left.
+pub fn fit_cooling_coefficient(
+    measurements: &[Measurement],
+    initial_temperature: Temperature,
+    drink: &Drink,
+    ambience: &Ambience,
+) -> Option<f64> {
+    let sink = sink_temperature(drink, ambience);
+    let (sum_ty, sum_tt) = measurements
+        .iter()
+        .filter(|m| m.seconds > 0.)
+        .filter(|m| m.temperature.as_kelvin() > drink.freezing_point.as_kelvin())
+        .filter_map(|m| {
+            let gradient = (m.temperature - sink) / (initial_temperature - sink);
+
+            (gradient > 0. && gradient < 1.).then(|| (m.seconds, gradient.ln()))
+        })
+        .fold((0., 0.), |(sum_ty, sum_tt), (t, y)| {
+            (sum_ty + t * y, sum_tt + t * t)
+        });
+
+    if sum_tt == 0. {
+        return None;
+    }
+
+    Some(-sum_ty / sum_tt)
+}
+
+/// Adds or replaces the calibration of the drink/ambience pair
+pub fn upsert_calibration(calibrations: &mut Vec<Calibration>, calibration: Calibration) {
+    match calibrations
+        .iter_mut()
+        .find(|c| c.drink_id == calibration.drink_id && c.ambience_id == calibration.ambience_id)
+    {
+        Some(existing) => *existing = calibration,
+        None => calibrations.push(calibration),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::helpers::{
+        ambience::Agitation,
+        catalog, cooling_coefficient,
+        drink::{Layer, LayerMaterial},
+        fixtures::assert_close,
+        temperature_after_time,
+    };
+
+    /// Measurements every 10 minutes of the predicted temperature
+    fn measurements(initial: Temperature, drink: &Drink, ambience: &Ambience) -> Vec<Measurement> {
+        (1..=12)
+            .map(|step| {
+                let time = Duration::minutes(10 * step);
+                Measurement {
+                    seconds: time.num_seconds() as f64,
+                    temperature: temperature_after_time(time, initial, drink, ambience).unwrap(),
+                }
+            })
+            .collect()
+    }
+
+    /// Fitted coefficient relative to the one the measurements came from
+    fn fit_ratio(drink: &Drink, ambience: &Ambience) -> f64 {
+        let initial = catalog::room_temperature().temperature;
+        let fitted = fit_cooling_coefficient(
+            &measurements(initial, drink, ambience),
+            initial,
+            drink,
+            ambience,
+        )
+        .unwrap();
+        fitted / cooling_coefficient(drink, ambience).unwrap()
+    }
+
+    #[test]
+    fn fit_recovers_the_model_coefficient() {
+        let ratio = fit_ratio(&catalog::beer_500_glass(), &catalog::fridge());
+        assert_close(ratio, 1., 1e-6);
+    }
+
+    #[test]
+    fn fit_of_a_wet_drink_uses_the_sink_temperature() {
+        let drink = catalog::beer_500_can().with_layer(Layer::new(LayerMaterial::WetTowel));
+        assert_close(fit_ratio(&drink, &catalog::fridge()), 1., 1e-6);
+    }
+
+    #[test]
+    fn calibration_applies_to_its_ambience_only() {
+        let drink = catalog::beer_500_glass();
+        let fridge = catalog::fridge();
+        let calibrations = [Calibration {
+            drink_id: drink.id,
+            ambience_id: fridge.id,
+            cooling_coefficient: 1e-3,
+        }];
+
+        let calibrated = drink.calibrated(&fridge, &calibrations);
+        assert_eq!(cooling_coefficient(&calibrated, &fridge).unwrap(), 1e-3);
+
+        let fan = fridge.clone().with_agitation(Agitation::Fan);
+        assert_ne!(cooling_coefficient(&calibrated, &fan).unwrap(), 1e-3);
+        let humid = fridge.with_humidity(0.9);
+        assert_ne!(cooling_coefficient(&calibrated, &humid).unwrap(), 1e-3);
+    }
+}
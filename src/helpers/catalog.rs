@@ -0,0 +1,314 @@
+use super::{
+    ambience::CoolingCapacity,
+    drink::{Container, ContainerMaterial, ContainerShape, DrinkType},
+    geometry::StandardContainer,
+    ice_bath::IceBath,
+    milliliters_to_m3,
+    serving::{serving_range, DrinkStyle},
+    Ambience, Drink, Fluid, Temperature, TemperatureUnit,
+};
+
+/// Slug used on the command line and the constructor of the entry
+pub type Entry<T> = (&'static str, fn() -> T);
+
+/// Built-in drinks by the slug used on the command line
+pub const DRINKS: [Entry<Drink>; 9] = [
+    ("beer-500-glass", beer_500_glass),
+    ("beer-330-glass", beer_330_glass),
+    ("beer-500-can", beer_500_can),
+    ("beer-330-can", beer_330_can),
+    ("lemonade-1000-pet", lemonade_1000_pet),
+    ("red-wine-750-glass", red_wine_750_glass),
+    ("white-wine-750-glass", white_wine_750_glass),
+    ("dessert-wine-375-glass", dessert_wine_375_glass),
+    ("spirits-700-glass", spirits_700_glass),
+];
+
+/// Built-in ambiences by the slug used on the command line
+pub const AMBIENCES: [Entry<Ambience>; 15] = [
+    ("cellar", cellar),
+    ("room", room_temperature),
+    ("summer-day", summer_day),
+    ("freezer", freezer),
+    ("ice-bath", ice_bath),
+    ("salted-ice-bath", salted_ice_bath),
+    ("fridge", fridge),
+    ("fan-freezer", fan_freezer),
+    ("snow", snow),
+    ("dry-ice-bath", dry_ice_bath),
+    ("schnaps", target_schnaps),
+    ("lemonade", target_lemonade),
+    ("beer", target_beer),
+    ("white-wine", target_white_wine),
+    ("red-wine", target_red_wine),
+];
+
+pub fn drink(slug: &str) -> Option<Drink> {
+    DRINKS
+        .iter()
+        .find(|(known, _)| *known == slug)
+        .map(|(_, drink)| drink())
+}
+
+pub fn ambience(slug: &str) -> Option<Ambience> {
+    AMBIENCES
+        .iter()
+        .find(|(known, _)| *known == slug)
+        .map(|(_, ambience)| ambience())
+}
+
+fn celsius(value: f64) -> Temperature {
+    Temperature::new_with_unit(value, TemperatureUnit::DegCelsius)
+}
+
+// Drinks
+
+pub fn beer_500_glass() -> Drink {
+    Drink::new(
+        "Bier",
+        "500ml Flasche",
+        "./assets/images/bier5.svg",
+        StandardContainer::NrwBottle.to_container(),
+        0.05,
+        0.,
+    )
+    .with_style(DrinkStyle::Pils)
+}
+
+pub fn beer_330_glass() -> Drink {
+    Drink::new(
+        "Bier",
+        "330ml Flasche",
+        "./assets/images/bier5.svg",
+        StandardContainer::LongneckBottle.to_container(),
+        0.05,
+        0.,
+    )
+    .with_style(DrinkStyle::Pils)
+}
+
+pub fn beer_500_can() -> Drink {
+    Drink::new(
+        "Bier",
+        "500ml Dose",
+        "./assets/images/can5.svg",
+        StandardContainer::Can500.to_container(),
+        0.05,
+        0.,
+    )
+    .with_style(DrinkStyle::Pils)
+}
+
+pub fn beer_330_can() -> Drink {
+    Drink::new(
+        "Bier",
+        "330ml Dose",
+        "./assets/images/can33.svg",
+        StandardContainer::Can330.to_container(),
+        0.05,
+        0.,
+    )
+    .with_style(DrinkStyle::Pils)
+}
+
+pub fn lemonade_1000_pet() -> Drink {
+    Drink::new(
+        "Limonade",
+        "1L Flasche",
+        "./assets/images/coke.svg",
+        StandardContainer::PetBottle1000.to_container(),
+        0.00,
+        106.,
+    )
+    .with_type(DrinkType::Lemonade)
+}
+
+pub fn red_wine_750_glass() -> Drink {
+    Drink::new(
+        "Rotwein",
+        "750ml Flasche",
+        "./assets/images/wein_rot.svg",
+        StandardContainer::BordeauxBottle750.to_container(),
+        0.15,
+        0.,
+    )
+    .with_style(DrinkStyle::RedWine)
+}
+
+pub fn white_wine_750_glass() -> Drink {
+    Drink::new(
+        "Weißwein",
+        "750ml Flasche",
+        "./assets/images/wein_weiss.svg",
+        StandardContainer::BordeauxBottle750.to_container(),
+        0.12,
+        4.,
+    )
+    .with_style(DrinkStyle::WhiteWine)
+}
+
+pub fn dessert_wine_375_glass() -> Drink {
+    Drink::new(
+        "Dessertwein",
+        "375ml Flasche",
+        "./assets/images/wein_rot.svg",
+        Container::new(
+            milliliters_to_m3(375.),
+            ContainerMaterial::Glass,
+            ContainerShape::WineBottle,
+        ),
+        0.13,
+        120.,
+    )
+    .with_style(DrinkStyle::DessertWine)
+}
+
+pub fn spirits_700_glass() -> Drink {
+    Drink::new(
+        "Schnaps",
+        "700ml Flasche",
+        "./assets/images/vodka.svg",
+        StandardContainer::SpiritsBottle700.to_container(),
+        0.40,
+        0.,
+    )
+    .with_type(DrinkType::Schnaps)
+}
+
+// Initial Temperatures
+
+pub fn cellar() -> Ambience {
+    Ambience::new(
+        "Kellerkalt",
+        "./assets/images/ioicon/thermometer-outline.svg",
+        celsius(14.0),
+        None,
+    )
+}
+
+pub fn room_temperature() -> Ambience {
+    Ambience::new(
+        "Raumtemperatur",
+        "./assets/images/ioicon/partly-sunny-outline.svg",
+        celsius(20.0),
+        None,
+    )
+}
+
+pub fn summer_day() -> Ambience {
+    Ambience::new(
+        "Heißer Sommertag",
+        "./assets/images/ioicon/sunny-outline.svg",
+        celsius(30.0),
+        None,
+    )
+}
+
+// Ambient Temperatures
+
+pub fn freezer() -> Ambience {
+    Ambience::new(
+        "Eisfach",
+        "./assets/images/flake3.svg",
+        celsius(-18.0),
+        Some(Fluid::Air),
+    )
+    .with_capacity(CoolingCapacity::FREEZER)
+}
+
+/// Ice cubes from a freezer bag in a bucket of water
+const ICE_BATH: IceBath = IceBath::new(1.5, 0.5, 0.);
+
+pub fn ice_bath() -> Ambience {
+    ICE_BATH.to_ambience()
+}
+
+pub fn salted_ice_bath() -> Ambience {
+    ICE_BATH.with_salt(ICE_BATH.suggested_salt()).to_ambience()
+}
+
+pub fn fridge() -> Ambience {
+    Ambience::new(
+        "Kühlschrank",
+        "./assets/images/ioicon/thermometer-outline.svg",
+        celsius(5.0),
+        Some(Fluid::Air),
+    )
+    .with_capacity(CoolingCapacity::FRIDGE)
+}
+
+pub fn fan_freezer() -> Ambience {
+    Ambience::new(
+        "Eisfach mit Ventilator",
+        "./assets/images/flake3.svg",
+        celsius(-18.0),
+        Some(Fluid::ForcedAir),
+    )
+    .with_capacity(CoolingCapacity::FREEZER)
+}
+
+pub fn snow() -> Ambience {
+    Ambience::new(
+        "Schneehaufen",
+        "./assets/images/flake.svg",
+        celsius(-5.0),
+        Some(Fluid::Snow),
+    )
+}
+
+/// Dry ice in ethanol, sublimating dry ice keeps the bath at -78 °C
+pub fn dry_ice_bath() -> Ambience {
+    Ambience::new(
+        "Trockeneis-Alkoholbad",
+        "./assets/images/flake.svg",
+        celsius(-78.0),
+        Some(Fluid::Ethanol),
+    )
+}
+
+// Target Temperatures, the ideal serving temperatures
+
+pub fn target_schnaps() -> Ambience {
+    Ambience::new(
+        "Optimal für Schnaps",
+        "./assets/images/vodka.svg",
+        serving_range(DrinkType::Schnaps, None).ideal,
+        None,
+    )
+}
+
+pub fn target_lemonade() -> Ambience {
+    Ambience::new(
+        "Optimal für Limonade",
+        "./assets/images/coke.svg",
+        serving_range(DrinkType::Lemonade, None).ideal,
+        None,
+    )
+}
+
+pub fn target_beer() -> Ambience {
+    Ambience::new(
+        "Optimal für Bier",
+        "./assets/images/bier5.svg",
+        serving_range(DrinkType::Beer, None).ideal,
+        None,
+    )
+}
+
+pub fn target_white_wine() -> Ambience {
+    Ambience::new(
+        "Optimal für Weißwein",
+        "./assets/images/wein_weiss.svg",
+        serving_range(DrinkType::Wine, Some(DrinkStyle::WhiteWine)).ideal,
+        None,
+    )
+}
+
+pub fn target_red_wine() -> Ambience {
+    Ambience::new(
+        "Optimal für Rotwein",
+        "./assets/images/wein_rot.svg",
+        serving_range(DrinkType::Wine, Some(DrinkStyle::RedWine)).ideal,
+        None,
+    )
+}
@@ -1,10 +1,17 @@
 use std::ops::{Add, Div, Sub};
 
+use serde::{Deserialize, Serialize};
+use uom::si::{area::square_meter, f64::Length, length::meter, volume::cubic_meter};
 use uuid::Uuid;
 
-use super::calculate_freezing_point;
+use super::{
+    ambience::Ambience,
+    geometry::{ContainerDimensions, StandardContainer},
+    mixture::{Mixture, HEAT_CAPACITY_SUGAR},
+    serving::DrinkStyle,
+};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Drink {
     pub id: Uuid,
     pub name: String,
@@ -14,14 +21,27 @@ pub struct Drink {
     /// Value between 0. and 1., volumetric percentage of
     /// ethanol in fluid.
     pub alcohol_percentage: f64,
-    // sugar_percentage: f64,
-    // pub drink_type: DrinkType,
-    // pub heat_capacity: f64,
+    /// Dissolved sugar and extract in g / l
+    #[serde(default)]
+    pub sugar_content: f64,
+    #[serde(default)]
+    pub drink_type: DrinkType,
+    /// Narrows down the serving temperature, see [`Drink::serving_range`]
+    #[serde(default)]
+    pub style: Option<DrinkStyle>,
+    /// Heat capacity of the liquid drink in J / K
+    pub heat_capacity: f64,
+    /// Heat capacity of the drink once its water is frozen in J / K
+    pub heat_capacity_frozen: f64,
+    /// Heat that has to be removed to freeze all of the
+    /// water in the drink, in J
+    pub latent_heat: f64,
     // pub fluid_heat_transfer_coefficient: f64,
     // pub container_heat_transfer_coefficient: f64,
-    /// First value is for ambient air (freezer, fridge),
-    /// Second value for ambient water (ice bath)
-    pub cooling_coefficient: (f64, f64),
+    /// Id of an ambience and the cooling coefficient in 1 / s fitted
+    /// to measurements in it, replaces the calculated one there
+    #[serde(default)]
+    pub calibration: Option<(Uuid, f64)>,
     pub freezing_point: Temperature,
 }
 
@@ -32,100 +52,325 @@ impl Drink {
         path_to_image: &str,
         container: Container,
         alcohol_percentage: f64,
+        sugar_content: f64,
     ) -> Self {
-        // Density -- kg / m^3
-        let water_density = Fluid::Water.get_density();
-        let ethanol_density = Fluid::Ethanol.get_density();
+        let mixture = Mixture::new(alcohol_percentage).with_sugar(sugar_content);
 
         // Volume -- m^3
         let total_volume = container.volume;
-        let water_volume_percentage = 1. - alcohol_percentage;
-        let water_volume = total_volume * water_volume_percentage;
-        let ethanol_volume = total_volume * alcohol_percentage;
 
         // Mass -- kg
-        let water_mass = water_density * water_volume;
-        let ethanol_mass = ethanol_density * ethanol_volume;
-        let total_mass = water_mass + ethanol_mass;
-
-        // Heat capacity of two mixed fluids
-        // (https://thermtest.com/thermal-resources/rule-of-mixtures)
-        // Heat capacity -- J / (kg * K)
-        let water_heat_capacity = Fluid::Water.get_heat_capacity();
-        let ethanol_heat_capacity = Fluid::Ethanol.get_heat_capacity();
-
-        let total_heat_capacity_coefficient = water_heat_capacity * (water_mass / total_mass)
-            + ethanol_heat_capacity * (ethanol_mass / total_mass);
-
-        let total_heat_capacity = total_mass * total_heat_capacity_coefficient;
-
-        // Heat transfer coefficient -- W / (m^2 * K)
-        // Todo: calculate htc based on ethanol content
-        // let fluid_heat_transfer_coefficient = Fluid::Water.get_heat_transfer_coefficient();
-        let fluid_heat_transfer_coefficient = 400.0;
-        let container_heat_transfer_coefficient = container.get_heat_transfer_coefficient();
-
-        // Todo: Can I precalculate the area based on volume etc.?
-        //
-
-        let total_heat_transfer_coefficient_ambient_air = 1.
-            / (1. / Fluid::Air.get_heat_transfer_coefficient()
-                + 1. / container_heat_transfer_coefficient
-                + 1. / fluid_heat_transfer_coefficient);
-        let total_heat_transfer_coefficient_ambient_water = 1.
-            / (1. / Fluid::Water.get_heat_transfer_coefficient()
-                + 1. / container_heat_transfer_coefficient
-                + 1. / fluid_heat_transfer_coefficient);
-
-        let cooling_coefficient_air = total_heat_transfer_coefficient_ambient_air
-            * container.surface_area
-            / total_heat_capacity;
-        let cooling_coefficient_water = total_heat_transfer_coefficient_ambient_water
-            * container.surface_area
-            / total_heat_capacity;
-
-        let cooling_coefficient = (cooling_coefficient_air, cooling_coefficient_water);
-
-        let freezing_point = calculate_freezing_point(alcohol_percentage);
-
-        Drink {
-            id: Uuid::new_v4(),
+        // Ethanol is measured by volume before mixing, the mixture
+        // itself takes up less space than both parts did
+        let total_mass = mixture.density() * total_volume;
+        let ethanol_mass = total_mass * mixture.ethanol_mass_fraction();
+        let sugar_mass = total_mass * mixture.sugar_mass_fraction();
+        let water_mass = total_mass - ethanol_mass - sugar_mass;
+
+        // Heat capacity -- J / K
+        let total_heat_capacity = total_mass * mixture.heat_capacity();
+        let ethanol_heat_capacity = Mixture::new(1.).heat_capacity();
+
+        // Once frozen, the water fraction turns to ice, which stores
+        // roughly half as much heat as liquid water
+        let total_heat_capacity_frozen = water_mass * HEAT_CAPACITY_ICE
+            + ethanol_mass * ethanol_heat_capacity
+            + sugar_mass * HEAT_CAPACITY_SUGAR;
+
+        // Latent heat -- J
+        let latent_heat = water_mass * LATENT_HEAT_OF_FUSION_WATER;
+
+        let freezing_point = mixture.freezing_point();
+
+        let mut drink = Drink {
+            id: Uuid::nil(),
             name: String::from(name),
             description: String::from(description),
             path_to_image: String::from(path_to_image),
             container: container.clone(),
             alcohol_percentage,
-            cooling_coefficient,
+            sugar_content,
+            drink_type: DrinkType::Other,
+            style: None,
+            calibration: None,
             freezing_point,
-        }
+            heat_capacity: total_heat_capacity,
+            heat_capacity_frozen: total_heat_capacity_frozen,
+            latent_heat,
+        };
+        drink.id = drink.content_id();
+        drink
+    }
+
+    /// Same drink, same id, so calibrations survive a reload
+    pub(crate) fn content_id(&self) -> Uuid {
+        let key = format!(
+            "{}/{}/{}/{}/{}/{:?}/{:?}",
+            self.name,
+            self.description,
+            self.container.key(),
+            self.alcohol_percentage,
+            self.sugar_content,
+            self.drink_type,
+            self.style
+        );
+        Uuid::new_v5(&Uuid::NAMESPACE_OID, key.as_bytes())
+    }
+
+    /// Cooling coefficient in 1 / s fitted to measurements in the
+    /// ambience, see [`Drink::calibration`]
+    pub fn calibrated_cooling_coefficient(&self, ambience: &Ambience) -> Option<f64> {
+        self.calibration
+            .filter(|(ambience_id, _)| *ambience_id == ambience.id)
+            .map(|(_, cooling_coefficient)| cooling_coefficient)
+    }
+
+    /// Drink with another layer wrapped around its container, e.g. a
+    /// koozie, cools differently and so gets an id of its own
+    pub fn with_layer(mut self, layer: Layer) -> Self {
+        self.container.layers.push(layer);
+        self.id = self.content_id();
+        self
+    }
+
+    /// Heat transfer coefficient from the outside of the container
+    /// into the liquid, in W / (m^2 * K)
+    pub fn inner_heat_transfer_coefficient(&self) -> f64 {
+        let fluid_heat_transfer_coefficient = Mixture::new(self.alcohol_percentage)
+            .with_sugar(self.sugar_content)
+            .heat_transfer_coefficient();
+        let container_heat_transfer_coefficient = self.container.get_heat_transfer_coefficient();
+
+        1. / (1. / container_heat_transfer_coefficient + 1. / fluid_heat_transfer_coefficient)
+    }
+
+    /// Cooling coefficient in 1 / s given the heat transfer
+    /// coefficient outside of the container, in W / (m^2 * K)
+    pub fn cooling_coefficient(&self, outer_heat_transfer_coefficient: f64) -> f64 {
+        let total_heat_transfer_coefficient = 1.
+            / (1. / outer_heat_transfer_coefficient + 1. / self.inner_heat_transfer_coefficient());
+
+        total_heat_transfer_coefficient * self.container.surface_area / self.heat_capacity
+    }
+
+    pub fn with_type(mut self, drink_type: DrinkType) -> Self {
+        self.drink_type = drink_type;
+        self.id = self.content_id();
+        self
+    }
+
+    /// Sets the style and the type it belongs to
+    pub fn with_style(mut self, style: DrinkStyle) -> Self {
+        self.drink_type = style.drink_type();
+        self.style = Some(style);
+        self.id = self.content_id();
+        self
     }
 }
 
-#[derive(Debug, Clone, Default)]
+/// Latent heat of fusion of water in J / kg
+pub const LATENT_HEAT_OF_FUSION_WATER: f64 = 333_550.;
+
+/// Heat capacity of ice (around -10 °C) in J / (kg * K)
+pub const HEAT_CAPACITY_ICE: f64 = 2050.;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Container {
     /// Volume in m^3
     pub volume: f64,
-    // /// Thickness of the wall in m
-    // pub wall_thickness: f64,
     /// Surface area of the container in m^2
     pub surface_area: f64,
     pub material: ContainerMaterial,
     pub shape: ContainerShape,
+    /// Thickness of the wall in m, `None` for the usual one of the material
+    #[serde(default)]
+    pub wall_thickness: Option<f64>,
+    /// Layers on the wall, from the inside out
+    #[serde(default)]
+    pub layers: Vec<Layer>,
+}
+
+impl Container {
+    /// Container with a surface area estimated from its volume,
+    /// assuming it is proportioned like a typical container of
+    /// its shape
+    pub fn new(volume: f64, material: ContainerMaterial, shape: ContainerShape) -> Self {
+        let reference = shape.get_standard_container().to_container();
+        // Surface area of similar bodies grows with volume^(2/3)
+        let surface_area = reference.surface_area * (volume / reference.volume).powf(2. / 3.);
+
+        Container {
+            volume,
+            surface_area,
+            material,
+            shape,
+            wall_thickness: None,
+            layers: vec![],
+        }
+    }
+
+    /// Container with volume and surface area calculated
+    /// from its outer dimensions
+    pub fn from_dimensions(
+        shape: ContainerShape,
+        material: ContainerMaterial,
+        dimensions: ContainerDimensions,
+    ) -> Self {
+        let wall_thickness = Length::new::<meter>(material.get_thickness());
+
+        Container {
+            volume: dimensions.inner_volume(wall_thickness).get::<cubic_meter>(),
+            surface_area: dimensions.surface_area().get::<square_meter>(),
+            material,
+            shape,
+            wall_thickness: None,
+            layers: vec![],
+        }
+    }
+
+    pub fn with_wall_thickness(mut self, wall_thickness: f64) -> Self {
+        self.wall_thickness = Some(wall_thickness);
+        self
+    }
+
+    pub fn with_layer(mut self, layer: Layer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    /// Thickness of the wall in m
+    pub fn get_wall_thickness(&self) -> f64 {
+        self.wall_thickness
+            .unwrap_or_else(|| self.material.get_thickness())
+    }
+
+    /// Thickness of the wall and all layers in m
+    pub fn get_total_thickness(&self) -> f64 {
+        self.get_wall_thickness() + self.layers.iter().map(|layer| layer.thickness).sum::<f64>()
+    }
+
+    /// Thermal resistance of the wall and all layers in m^2 * K / W,
+    /// layers are stacked, so their resistances add up
+    pub fn get_thermal_resistance(&self) -> f64 {
+        self.get_wall_thickness() / self.material.get_thermal_conductivity()
+            + self
+                .layers
+                .iter()
+                .map(|layer| 1. / layer.get_heat_transfer_coefficient())
+                .sum::<f64>()
+    }
+
+    /// Text the id of a drink is derived from
+    fn key(&self) -> String {
+        let layers = self
+            .layers
+            .iter()
+            .map(|layer| format!("{:?}:{}", layer.material, layer.thickness))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "{}/{}/{:?}/{:?}/{:?}/[{layers}]",
+            self.volume, self.surface_area, self.material, self.shape, self.wall_thickness
+        )
+    }
 }
 
 impl HeatTransferCoefficient for Container {
     fn get_heat_transfer_coefficient(&self) -> f64 {
-        self.material.get_heat_transfer_coefficient()
+        1. / self.get_thermal_resistance()
     }
 }
 
 impl ThermalConductivity for Container {
+    /// Averaged over the wall and all layers
+    fn get_thermal_conductivity(&self) -> f64 {
+        self.get_total_thickness() / self.get_thermal_resistance()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Something covering the wall of a container
+pub struct Layer {
+    pub material: LayerMaterial,
+    /// Thickness in m
+    pub thickness: f64,
+}
+
+impl Layer {
+    /// Layer of the usual thickness of the material
+    pub fn new(material: LayerMaterial) -> Self {
+        Layer {
+            material,
+            thickness: material.get_thickness(),
+        }
+    }
+
+    pub fn with_thickness(mut self, thickness: f64) -> Self {
+        self.thickness = thickness;
+        self
+    }
+}
+
+impl HeatTransferCoefficient for Layer {
+    fn get_heat_transfer_coefficient(&self) -> f64 {
+        self.material.get_thermal_conductivity() / self.thickness
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LayerMaterial {
+    /// Paper label glued onto a bottle, assumed to cover all of it
+    PaperLabel,
+    /// Lacquer on the inside of a can
+    PlasticLiner,
+    /// Neoprene sleeve, a koozie
+    Neoprene,
+    /// Wrap of wet paper towel or cloth
+    WetTowel,
+}
+
+impl LayerMaterial {
+    pub const ALL: [LayerMaterial; 4] = [
+        Self::PaperLabel,
+        Self::PlasticLiner,
+        Self::Neoprene,
+        Self::WetTowel,
+    ];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::PaperLabel => "Papieretikett",
+            Self::PlasticLiner => "Innenbeschichtung",
+            Self::Neoprene => "Neopren-Kühlhülle",
+            Self::WetTowel => "Nasses Tuch",
+        }
+    }
+
+    /// Usual thickness in m
+    pub fn get_thickness(&self) -> f64 {
+        match self {
+            Self::PaperLabel => 0.0001,
+            Self::PlasticLiner => 0.00001,
+            Self::Neoprene => 0.004,
+            Self::WetTowel => 0.002,
+        }
+    }
+}
+
+impl ThermalConductivity for LayerMaterial {
     fn get_thermal_conductivity(&self) -> f64 {
-        self.material.get_thermal_conductivity()
+        match self {
+            Self::PaperLabel => 0.05,
+            // Epoxy lacquer
+            Self::PlasticLiner => 0.2,
+            Self::Neoprene => 0.054,
+            // Cotton soaked with water, mostly the water conducts
+            Self::WetTowel => 0.4,
+        }
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ContainerMaterial {
     Plastic,
     #[default]
@@ -134,11 +379,21 @@ pub enum ContainerMaterial {
 }
 
 impl ContainerMaterial {
+    pub const ALL: [ContainerMaterial; 3] = [Self::Glass, Self::Aluminium, Self::Plastic];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Plastic => "Plastik",
+            Self::Glass => "Glas",
+            Self::Aluminium => "Aluminium",
+        }
+    }
+
     /// Thickness of wall of bottle in meters.
     ///
     /// The thicker the wall, the longer it takes for
     /// heat/power to travel through the material.
-    fn get_thickness(&self) -> f64 {
+    pub fn get_thickness(&self) -> f64 {
         match self {
             Self::Plastic => 0.001,
             Self::Glass => 0.0045,
@@ -167,18 +422,38 @@ impl HeatTransferCoefficient for ContainerMaterial {
     }
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 /// Mainly for classification
 pub enum DrinkType {
-    #[default]
     Beer,
     Wine,
     Lemonade,
     Schnaps,
+    #[default]
     Other,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+impl DrinkType {
+    pub const ALL: [DrinkType; 5] = [
+        Self::Beer,
+        Self::Wine,
+        Self::Lemonade,
+        Self::Schnaps,
+        Self::Other,
+    ];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Beer => "Bier",
+            Self::Wine => "Wein",
+            Self::Lemonade => "Limonade",
+            Self::Schnaps => "Schnaps",
+            Self::Other => "Sonstiges",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ContainerShape {
     #[default]
     BeerBottle,
@@ -190,46 +465,48 @@ pub enum ContainerShape {
     SchnapsBottle,
 }
 
-#[derive(Debug, Clone)]
-pub enum Fluid {
-    Air,
-    Water,
-    Ethanol,
-}
+impl ContainerShape {
+    pub const ALL: [ContainerShape; 5] = [
+        Self::BeerBottle,
+        Self::WineBottle,
+        Self::Can,
+        Self::PetBottle,
+        Self::SchnapsBottle,
+    ];
 
-impl Fluid {
-    /// Density in kg / m^3
-    pub fn get_density(&self) -> f64 {
+    pub fn get_name(&self) -> &'static str {
         match self {
-            Fluid::Water => 1000.,
-            Fluid::Ethanol => 789.,
-            _ => todo!("air density not needed"),
+            Self::BeerBottle => "Bierflasche",
+            Self::WineBottle => "Weinflasche",
+            Self::Can => "Dose",
+            Self::PetBottle => "PET-Flasche",
+            Self::SchnapsBottle => "Schnapsflasche",
         }
     }
 
-    /// In J / (kg * K)
-    pub fn get_heat_capacity(&self) -> f64 {
+    pub fn get_path_to_image(&self) -> &'static str {
         match self {
-            Fluid::Water => 4182.,
-            Fluid::Ethanol => 2460.,
-            // Fluid::Ethanol => 3460.,
-            _ => todo!("air capacity not needed"),
+            Self::BeerBottle => "./assets/images/bier5.svg",
+            Self::WineBottle => "./assets/images/wein_weiss.svg",
+            Self::Can => "./assets/images/can5.svg",
+            Self::PetBottle => "./assets/images/coke.svg",
+            Self::SchnapsBottle => "./assets/images/vodka.svg",
         }
     }
-}
 
-impl HeatTransferCoefficient for Fluid {
-    fn get_heat_transfer_coefficient(&self) -> f64 {
+    /// Typical container of this shape
+    pub fn get_standard_container(&self) -> StandardContainer {
         match self {
-            // Fluid::Air => 40.,
-            Fluid::Air => 25.,
-            Fluid::Water => 1000.,
-            _ => todo!("other fluids not implemented"),
+            Self::BeerBottle => StandardContainer::NrwBottle,
+            Self::WineBottle => StandardContainer::BordeauxBottle750,
+            Self::Can => StandardContainer::Can500,
+            Self::PetBottle => StandardContainer::PetBottle1000,
+            Self::SchnapsBottle => StandardContainer::SpiritsBottle700,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Temperature(f64);
 
 impl Temperature {
@@ -241,6 +518,7 @@ impl Temperature {
         match unit {
             TemperatureUnit::Kelvin => Temperature(value),
             TemperatureUnit::DegCelsius => Temperature(value + 273.15),
+            TemperatureUnit::DegFahrenheit => Temperature((value + 459.67) * 5. / 9.),
         }
     }
 
@@ -248,6 +526,7 @@ impl Temperature {
         match unit {
             TemperatureUnit::Kelvin => self.0,
             TemperatureUnit::DegCelsius => self.0 - 273.15,
+            TemperatureUnit::DegFahrenheit => self.0 * 9. / 5. - 459.67,
         }
     }
 
@@ -259,18 +538,18 @@ impl Temperature {
         self.as_unit(TemperatureUnit::DegCelsius)
     }
 
+    pub fn as_deg_fahrenheit(&self) -> f64 {
+        self.as_unit(TemperatureUnit::DegFahrenheit)
+    }
+
     pub fn format(&self, unit: TemperatureUnit, append_unit: bool) -> String {
-        let raw = match unit {
-            TemperatureUnit::DegCelsius => self.as_deg_celsius(),
-            TemperatureUnit::Kelvin => self.as_kelvin(),
-        };
+        let raw = self.as_unit(unit);
 
-        let append = match unit {
-            TemperatureUnit::DegCelsius => " °C",
-            TemperatureUnit::Kelvin => " K",
-        };
+        if !append_unit {
+            return format!("{:.0}", raw);
+        }
 
-        format!("{:.0}{}", raw, append)
+        format!("{:.0} {}", raw, unit.get_symbol())
     }
 }
 
@@ -298,11 +577,25 @@ impl Div for Temperature {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum TemperatureUnit {
     Kelvin,
+    #[default]
     DegCelsius,
-    // DegFahrenheit,
+    DegFahrenheit,
+}
+
+impl TemperatureUnit {
+    /// Units offered to the user
+    pub const DISPLAY_UNITS: [TemperatureUnit; 2] = [Self::DegCelsius, Self::DegFahrenheit];
+
+    pub fn get_symbol(&self) -> &'static str {
+        match self {
+            Self::Kelvin => "K",
+            Self::DegCelsius => "°C",
+            Self::DegFahrenheit => "°F",
+        }
+    }
 }
 
 /// Heat transfer coefficient in W / (m^2 * K)
@@ -332,3 +625,88 @@ pub trait ThermalConductivity {
 //     /// https://en.wikipedia.org/wiki/Newton%27s_law_of_cooling
 //     fn get_cooling_coefficient(&self) -> f64;
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{catalog, fixtures::assert_close, milliliters_to_m3};
+
+    #[test]
+    fn layer_resistances_add_up() {
+        let bottle = StandardContainer::NrwBottle.to_container();
+        let wrapped = bottle
+            .clone()
+            .with_layer(Layer::new(LayerMaterial::Neoprene));
+
+        // 4.5 mm of glass and 4 mm of neoprene
+        assert_close(bottle.get_thermal_resistance(), 0.0045 / 0.037, 1e-12);
+        assert_close(
+            wrapped.get_thermal_resistance(),
+            0.0045 / 0.037 + 0.004 / 0.054,
+            1e-12,
+        );
+        assert_close(wrapped.get_total_thickness(), 0.0085, 1e-12);
+    }
+
+    #[test]
+    fn wall_thickness_replaces_the_usual_one() {
+        let thin = StandardContainer::NrwBottle
+            .to_container()
+            .with_wall_thickness(0.003);
+        assert_close(thin.get_thermal_resistance(), 0.003 / 0.037, 1e-12);
+    }
+
+    #[test]
+    fn surface_grows_with_volume_to_the_two_thirds() {
+        let reference = ContainerShape::Can.get_standard_container().to_container();
+        let double = Container::new(
+            2. * reference.volume,
+            ContainerMaterial::Aluminium,
+            ContainerShape::Can,
+        );
+        assert_close(
+            double.surface_area,
+            reference.surface_area * 2f64.powf(2. / 3.),
+            1e-12,
+        );
+        let can = Container::new(
+            milliliters_to_m3(500.),
+            ContainerMaterial::Aluminium,
+            ContainerShape::Can,
+        );
+        assert_close(can.surface_area, reference.surface_area, 1e-12);
+    }
+
+    #[test]
+    fn wrapped_drink_gets_its_own_id() {
+        let beer = catalog::beer_500_can();
+        let wrapped = beer.clone().with_layer(Layer::new(LayerMaterial::Neoprene));
+        assert_ne!(beer.id, wrapped.id);
+        assert!(wrapped.inner_heat_transfer_coefficient() < beer.inner_heat_transfer_coefficient());
+    }
+
+    #[test]
+    fn sugar_lowers_freezing_point_and_heat_capacity() {
+        let can = || StandardContainer::Can330.to_container();
+        let water = Drink::new("Limonade", "", "", can(), 0., 0.);
+        let sweetened = Drink::new("Limonade", "", "", can(), 0., 106.);
+
+        assert_eq!(water.freezing_point.as_deg_celsius(), 0.);
+        assert!(sweetened.freezing_point.as_deg_celsius() < -1.);
+        assert!(sweetened.heat_capacity < water.heat_capacity);
+        assert!(sweetened.latent_heat < water.latent_heat);
+        assert_ne!(sweetened.id, water.id);
+    }
+
+    #[test]
+    fn style_is_part_of_the_id() {
+        let can = || StandardContainer::Can330.to_container();
+        let beer = Drink::new("Bier", "", "", can(), 0.05, 0.);
+        let pils = beer.clone().with_style(DrinkStyle::Pils);
+        let weizen = beer.clone().with_style(DrinkStyle::Weizen);
+
+        assert_ne!(beer.id, pils.id);
+        assert_ne!(pils.id, weizen.id);
+        assert_eq!(pils.id, pils.content_id());
+    }
+}
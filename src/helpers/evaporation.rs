@@ -0,0 +1,125 @@
+//! Evaporative cooling of a drink wrapped in a wet towel
+//!
+//! Water evaporating from the towel takes its latent heat from the
+//! drink, the less humid and the warmer the air, the more of it
+//! evaporates. Linearized around the temperature of the air, this
+//! acts like a better heat transfer towards a colder sink, about
+//! the wet-bulb temperature of the air.
+//!
+//! Assumes the towel stays wet for the whole time. Mass transfer
+//! follows from heat transfer by the Chilton-Colburn analogy, see
+//! <https://en.wikipedia.org/wiki/Chilton_and_Colburn_J-factor_analogy>
+
+use super::{
+    drink::{Drink, LayerMaterial},
+    Ambience, Fluid, Temperature, TemperatureUnit,
+};
+
+/// Relative humidity of air if the ambience doesn't know better
+pub const DEFAULT_HUMIDITY: f64 = 0.5;
+
+/// Latent heat of vaporization of water around room temperature in J / kg
+const LATENT_HEAT_OF_VAPORIZATION_WATER: f64 = 2_450_000.;
+/// Latent heat of sublimation of ice, the towel freezes below 0 °C, in J / kg
+const LATENT_HEAT_OF_SUBLIMATION_WATER: f64 = 2_834_000.;
+/// Lewis number of water vapour in air to the power of 2/3
+const LEWIS_FACTOR: f64 = 0.9;
+const MOLAR_MASS_WATER: f64 = 0.018015;
+const GAS_CONSTANT: f64 = 8.314;
+
+#[derive(Debug, Clone, Copy)]
+/// Effect of evaporation on the cooling of a drink
+pub struct Evaporation {
+    /// Added to the heat transfer coefficient of the air in W / (m^2 * K)
+    pub heat_transfer_coefficient: f64,
+    /// Temperature the wet drink cools towards
+    pub sink_temperature: Temperature,
+}
+
+impl Drink {
+    /// Whether the outermost layer of the container is a wet towel
+    pub fn is_wrapped_wet(&self) -> bool {
+        self.container
+            .layers
+            .last()
+            .is_some_and(|layer| layer.material == LayerMaterial::WetTowel)
+    }
+}
+
+/// Evaporation from the drink in the ambience, `None` if the drink
+/// isn't wrapped wet or doesn't sit in air
+pub fn evaporation(drink: &Drink, ambience: &Ambience) -> Option<Evaporation> {
+    if !drink.is_wrapped_wet() || !matches!(ambience.fluid, Fluid::Air | Fluid::ForcedAir) {
+        return None;
+    }
+
+    let air = ambience.temperature.as_deg_celsius();
+    let humidity = ambience.humidity.unwrap_or(DEFAULT_HUMIDITY).clamp(0., 1.);
+    let latent_heat = match air < 0. {
+        true => LATENT_HEAT_OF_SUBLIMATION_WATER,
+        false => LATENT_HEAT_OF_VAPORIZATION_WATER,
+    };
+
+    // Mass transfer coefficient -- m / s
+    let heat_transfer_coefficient = ambience.heat_transfer_coefficient();
+    let mass_transfer_coefficient = heat_transfer_coefficient
+        / (ambience.fluid.get_density() * ambience.fluid.get_heat_capacity() * LEWIS_FACTOR);
+
+    // Evaporation grows with the vapour density at the surface, which
+    // rises about linearly with its temperature near the air's
+    let slope = (saturation_vapor_density(air + 0.05) - saturation_vapor_density(air - 0.05)) / 0.1;
+    let evaporation_coefficient = mass_transfer_coefficient * latent_heat * slope;
+
+    // Dry air takes up vapour even from a surface as warm as itself
+    let depression =
+        mass_transfer_coefficient * latent_heat * (1. - humidity) * saturation_vapor_density(air)
+            / (heat_transfer_coefficient + evaporation_coefficient);
+
+    Some(Evaporation {
+        heat_transfer_coefficient: evaporation_coefficient,
+        sink_temperature: Temperature::new_with_unit(air - depression, TemperatureUnit::DegCelsius),
+    })
+}
+
+/// Mass of water vapour in saturated air in kg / m^3, over ice
+/// below 0 °C, from the Magnus formula
+fn saturation_vapor_density(celsius: f64) -> f64 {
+    let pressure = match celsius < 0. {
+        true => 611.2 * f64::exp(22.46 * celsius / (272.62 + celsius)),
+        false => 611.2 * f64::exp(17.62 * celsius / (243.12 + celsius)),
+    };
+    pressure * MOLAR_MASS_WATER / (GAS_CONSTANT * (celsius + 273.15))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{catalog, drink::Layer, fixtures::assert_close};
+
+    fn wet_beer() -> Drink {
+        catalog::beer_500_glass().with_layer(Layer::new(LayerMaterial::WetTowel))
+    }
+
+    #[test]
+    fn wet_towel_cools_towards_the_wet_bulb_temperature() {
+        // About 13.7 °C by psychrometric chart at 20 °C and 50 %
+        let room = catalog::room_temperature().with_humidity(0.5);
+        let evaporation = evaporation(&wet_beer(), &room).unwrap();
+        assert_close(evaporation.sink_temperature.as_deg_celsius(), 13.7, 1.);
+        assert!(evaporation.heat_transfer_coefficient > 0.);
+    }
+
+    #[test]
+    fn saturated_air_takes_up_no_vapour() {
+        let room = catalog::room_temperature().with_humidity(1.);
+        let sink = evaporation(&wet_beer(), &room).unwrap().sink_temperature;
+        assert_close(sink.as_deg_celsius(), 20., 1e-9);
+    }
+
+    #[test]
+    fn only_wet_drinks_in_air_evaporate() {
+        let room = catalog::room_temperature();
+        assert!(evaporation(&catalog::beer_500_glass(), &room).is_none());
+        assert!(evaporation(&wet_beer(), &catalog::ice_bath()).is_none());
+    }
+}
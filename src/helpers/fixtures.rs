@@ -0,0 +1,22 @@
+//! Shared values for the unit tests of the helpers
+
+use chrono::{DateTime, Local, TimeZone};
+
+use super::{Temperature, TemperatureUnit};
+
+/// Fixed wall clock time the tests start their timers and plans at
+pub fn start() -> DateTime<Local> {
+    Local.with_ymd_and_hms(2024, 6, 1, 18, 0, 0).unwrap()
+}
+
+pub fn celsius(value: f64) -> Temperature {
+    Temperature::new_with_unit(value, TemperatureUnit::DegCelsius)
+}
+
+/// Fails with both values if they are further apart than `tolerance`
+pub fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+    assert!(
+        (actual - expected).abs() <= tolerance,
+        "{actual} is not within {tolerance} of {expected}"
+    );
+}
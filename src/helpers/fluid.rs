@@ -0,0 +1,169 @@
+//! Media a drink can be cooled in
+//!
+//! Liquid baths transfer heat far better than still air, a fan
+//! helps air a lot and packed snow sits in between. Heat transfer
+//! coefficients are typical values for a bottle sized object.
+//!
+//! Sources, values rounded:
+//! - air: <https://www.engineeringtoolbox.com/air-properties-d_156.html>
+//! - brine: <https://www.engineeringtoolbox.com/sodium-chloride-water-d_1187.html>
+//! - snow: Sturm et al., "The thermal conductivity of seasonal snow", 1997
+
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    drink::{HeatTransferCoefficient, ThermalConductivity, HEAT_CAPACITY_ICE},
+    mixture::Mixture,
+    Temperature,
+};
+
+/// Mass fraction of salt at the eutectic point of brine
+pub const EUTECTIC_SALINITY: f64 = 0.233;
+/// Lowest temperature brine stays liquid at, in °C
+pub const EUTECTIC_TEMPERATURE: f64 = -21.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Fluid {
+    /// Still air, e.g. in a freezer or fridge
+    Air,
+    /// Air moved by a fan
+    ForcedAir,
+    Water,
+    /// Salt water, e.g. a salted ice bath
+    Brine {
+        /// Mass fraction of salt, between 0. and [`EUTECTIC_SALINITY`]
+        salinity: f64,
+    },
+    /// Bath of pure ethanol, e.g. with dry ice
+    Ethanol,
+    /// Packed snow around the drink
+    Snow,
+}
+
+impl Fluid {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Fluid::Air => "Luft",
+            Fluid::ForcedAir => "Luft mit Ventilator",
+            Fluid::Water => "Wasser",
+            Fluid::Brine { .. } => "Salzwasser",
+            Fluid::Ethanol => "Alkohol",
+            Fluid::Snow => "Schnee",
+        }
+    }
+
+    /// Density in kg / m^3
+    pub fn get_density(&self) -> f64 {
+        match self {
+            Fluid::Air | Fluid::ForcedAir => 1.29,
+            Fluid::Water => Mixture::new(0.).density(),
+            Fluid::Brine { salinity } => Mixture::new(0.).density() * (1. + 0.7 * salinity),
+            Fluid::Ethanol => Mixture::new(1.).density(),
+            Fluid::Snow => 300.,
+        }
+    }
+
+    /// In J / (kg * K)
+    pub fn get_heat_capacity(&self) -> f64 {
+        match self {
+            Fluid::Air | Fluid::ForcedAir => 1005.,
+            Fluid::Water => Mixture::new(0.).heat_capacity(),
+            Fluid::Brine { salinity } => Mixture::new(0.).heat_capacity() * (1. - 0.9 * salinity),
+            Fluid::Ethanol => Mixture::new(1.).heat_capacity(),
+            Fluid::Snow => HEAT_CAPACITY_ICE,
+        }
+    }
+
+    /// Temperatures in °C the fluid can surround a drink at, e.g.
+    /// water turns to ice below 0 °C and snow melts above it
+    pub fn temperature_range(&self) -> RangeInclusive<f64> {
+        match self {
+            Fluid::Air | Fluid::ForcedAir => f64::NEG_INFINITY..=f64::INFINITY,
+            Fluid::Water => 0.0..=100.,
+            Fluid::Brine { salinity } => brine_freezing_point(*salinity)..=100.,
+            Fluid::Ethanol => -114.0..=78.,
+            Fluid::Snow => f64::NEG_INFINITY..=0.,
+        }
+    }
+
+    pub fn allows_temperature(&self, temperature: Temperature) -> bool {
+        // Ice baths sit right at the freezing point of their brine
+        const TOLERANCE: f64 = 0.01;
+        let range = self.temperature_range();
+        let temperature = temperature.as_deg_celsius();
+
+        temperature >= range.start() - TOLERANCE && temperature <= range.end() + TOLERANCE
+    }
+}
+
+impl ThermalConductivity for Fluid {
+    fn get_thermal_conductivity(&self) -> f64 {
+        match self {
+            Fluid::Air | Fluid::ForcedAir => 0.024,
+            Fluid::Water => Mixture::new(0.).thermal_conductivity(),
+            Fluid::Brine { salinity } => {
+                Mixture::new(0.).thermal_conductivity() * (1. - 0.3 * salinity)
+            }
+            Fluid::Ethanol => Mixture::new(1.).thermal_conductivity(),
+            Fluid::Snow => 0.2,
+        }
+    }
+}
+
+impl HeatTransferCoefficient for Fluid {
+    fn get_heat_transfer_coefficient(&self) -> f64 {
+        match self {
+            // Fluid::Air => 40.,
+            Fluid::Air => 25.,
+            Fluid::ForcedAir => 50.,
+            Fluid::Water => 1000.,
+            // Salt makes the water more viscous and conduct heat worse
+            Fluid::Brine { salinity } => 1000. * (1. - 0.4 * salinity),
+            // Natural convection scales with the fluid like inside the drink
+            Fluid::Ethanol => {
+                1000. * Mixture::new(1.).heat_transfer_coefficient()
+                    / Mixture::new(0.).heat_transfer_coefficient()
+            }
+            // Conduction through a layer of about 5 mm of snow
+            Fluid::Snow => 40.,
+        }
+    }
+}
+
+/// Temperature in °C below which ice forms in brine of the given
+/// salinity, down to the eutectic point
+pub fn brine_freezing_point(salinity: f64) -> f64 {
+    let salinity = salinity.clamp(0., EUTECTIC_SALINITY);
+    (-(55. * salinity + 150. * salinity * salinity)).max(EUTECTIC_TEMPERATURE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::fixtures::{assert_close, celsius};
+
+    #[test]
+    fn brine_freezes_lower_with_more_salt() {
+        assert_eq!(brine_freezing_point(0.), 0.);
+        assert_close(brine_freezing_point(0.1), -7., 1e-9);
+        assert!(brine_freezing_point(1.) >= EUTECTIC_TEMPERATURE);
+    }
+
+    #[test]
+    fn fluids_only_surround_drinks_in_their_range() {
+        assert!(!Fluid::Water.allows_temperature(celsius(-5.)));
+        assert!(Fluid::Water.allows_temperature(celsius(0.)));
+        assert!(Fluid::Brine { salinity: 0.1 }.allows_temperature(celsius(-5.)));
+        assert!(!Fluid::Snow.allows_temperature(celsius(5.)));
+        assert!(Fluid::Air.allows_temperature(celsius(-80.)));
+    }
+
+    #[test]
+    fn water_transfers_heat_better_than_air() {
+        let air = Fluid::Air.get_heat_transfer_coefficient();
+        assert!(Fluid::ForcedAir.get_heat_transfer_coefficient() > air);
+        assert!(Fluid::Water.get_heat_transfer_coefficient() > 10. * air);
+    }
+}
@@ -0,0 +1,236 @@
+use std::f64::consts::PI;
+
+use uom::si::{
+    f64::{Area, Length, Volume},
+    length::millimeter,
+};
+
+use crate::calculate_surface_area::{area_of_bottle_container, area_of_cylindric_container};
+
+use super::{
+    drink::{Container, ContainerMaterial, ContainerShape},
+    milliliters_to_m3,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Outer dimensions of a container
+pub enum ContainerDimensions {
+    /// Plain cylinder, e.g. a can
+    Cylinder { diameter: Length, height: Length },
+    /// Cylindrical base with a cone shaped neck on top. The thin part
+    /// of the neck above the shoulder is ignored.
+    Bottle {
+        diameter_top_bottle: Length,
+        height_tapered_neck: Length,
+        diameter_base: Length,
+        height_base: Length,
+    },
+}
+
+impl ContainerDimensions {
+    /// Outer surface area that exchanges heat with the ambience
+    pub fn surface_area(&self) -> Area {
+        match *self {
+            Self::Cylinder { diameter, height } => area_of_cylindric_container(diameter, height),
+            Self::Bottle {
+                diameter_top_bottle,
+                height_tapered_neck,
+                diameter_base,
+                height_base,
+            } => area_of_bottle_container(
+                diameter_top_bottle,
+                height_tapered_neck,
+                diameter_base,
+                height_base,
+            ),
+        }
+    }
+
+    /// Volume inside the container walls of given thickness
+    pub fn inner_volume(&self, wall_thickness: Length) -> Volume {
+        match *self {
+            Self::Cylinder { diameter, height } => {
+                let radius = diameter / 2.0 - wall_thickness;
+                PI * radius * radius * (height - 2.0 * wall_thickness)
+            }
+            Self::Bottle {
+                diameter_top_bottle,
+                height_tapered_neck,
+                diameter_base,
+                height_base,
+            } => {
+                let a = diameter_top_bottle / 2.0 - wall_thickness;
+                let b = diameter_base / 2.0 - wall_thickness;
+                let h1 = height_base - wall_thickness;
+                let h2 = height_tapered_neck;
+
+                // Cylinder plus frustum of a cone
+                PI * b * b * h1 + PI * h2 / 3.0 * (a * a + a * b + b * b)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Common containers found in German supermarkets
+pub enum StandardContainer {
+    /// 0.5 l NRW beer bottle
+    NrwBottle,
+    /// 0.5 l Euro beer bottle
+    EuroBottle,
+    /// 0.33 l longneck beer bottle
+    LongneckBottle,
+    Can330,
+    Can500,
+    /// 1 l PET bottle for soft drinks
+    PetBottle1000,
+    /// 0.7 l bottle for spirits
+    SpiritsBottle700,
+    /// 0.75 l Bordeaux wine bottle
+    BordeauxBottle750,
+}
+
+impl StandardContainer {
+    pub const ALL: [StandardContainer; 8] = [
+        Self::NrwBottle,
+        Self::EuroBottle,
+        Self::LongneckBottle,
+        Self::Can330,
+        Self::Can500,
+        Self::PetBottle1000,
+        Self::SpiritsBottle700,
+        Self::BordeauxBottle750,
+    ];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::NrwBottle => "NRW-Flasche 0,5 l",
+            Self::EuroBottle => "Euroflasche 0,5 l",
+            Self::LongneckBottle => "Longneck 0,33 l",
+            Self::Can330 => "Dose 0,33 l",
+            Self::Can500 => "Dose 0,5 l",
+            Self::PetBottle1000 => "PET-Flasche 1 l",
+            Self::SpiritsBottle700 => "Spirituosenflasche 0,7 l",
+            Self::BordeauxBottle750 => "Bordeauxflasche 0,75 l",
+        }
+    }
+
+    pub fn get_shape(&self) -> ContainerShape {
+        match self {
+            Self::NrwBottle | Self::EuroBottle | Self::LongneckBottle => ContainerShape::BeerBottle,
+            Self::Can330 | Self::Can500 => ContainerShape::Can,
+            Self::PetBottle1000 => ContainerShape::PetBottle,
+            Self::SpiritsBottle700 => ContainerShape::SchnapsBottle,
+            Self::BordeauxBottle750 => ContainerShape::WineBottle,
+        }
+    }
+
+    pub fn get_material(&self) -> ContainerMaterial {
+        match self.get_shape() {
+            ContainerShape::Can => ContainerMaterial::Aluminium,
+            ContainerShape::PetBottle => ContainerMaterial::Plastic,
+            _ => ContainerMaterial::Glass,
+        }
+    }
+
+    pub fn get_dimensions(&self) -> ContainerDimensions {
+        let mm = Length::new::<millimeter>;
+        match self {
+            Self::NrwBottle => ContainerDimensions::Bottle {
+                diameter_top_bottle: mm(26.0),
+                height_tapered_neck: mm(97.0),
+                diameter_base: mm(67.0),
+                height_base: mm(160.0),
+            },
+            Self::EuroBottle => ContainerDimensions::Bottle {
+                diameter_top_bottle: mm(26.0),
+                height_tapered_neck: mm(95.0),
+                diameter_base: mm(71.5),
+                height_base: mm(130.0),
+            },
+            Self::LongneckBottle => ContainerDimensions::Bottle {
+                diameter_top_bottle: mm(26.0),
+                height_tapered_neck: mm(80.0),
+                diameter_base: mm(60.0),
+                height_base: mm(130.0),
+            },
+            Self::Can330 => ContainerDimensions::Cylinder {
+                diameter: mm(66.0),
+                height: mm(115.0),
+            },
+            Self::Can500 => ContainerDimensions::Cylinder {
+                diameter: mm(66.0),
+                height: mm(168.0),
+            },
+            Self::PetBottle1000 => ContainerDimensions::Bottle {
+                diameter_top_bottle: mm(28.0),
+                height_tapered_neck: mm(60.0),
+                diameter_base: mm(80.0),
+                height_base: mm(185.0),
+            },
+            Self::SpiritsBottle700 => ContainerDimensions::Bottle {
+                diameter_top_bottle: mm(28.0),
+                height_tapered_neck: mm(80.0),
+                diameter_base: mm(76.0),
+                height_base: mm(165.0),
+            },
+            Self::BordeauxBottle750 => ContainerDimensions::Bottle {
+                diameter_top_bottle: mm(29.5),
+                height_tapered_neck: mm(70.0),
+                diameter_base: mm(75.0),
+                height_base: mm(185.0),
+            },
+        }
+    }
+
+    /// Volume of the drink filled in, the nominal one, in m^3
+    pub fn get_fill_volume(&self) -> f64 {
+        milliliters_to_m3(match self {
+            Self::NrwBottle | Self::EuroBottle | Self::Can500 => 500.0,
+            Self::LongneckBottle | Self::Can330 => 330.0,
+            Self::PetBottle1000 => 1000.0,
+            Self::SpiritsBottle700 => 700.0,
+            Self::BordeauxBottle750 => 750.0,
+        })
+    }
+
+    /// Container holding its nominal fill, the simplified outline
+    /// only gives the surface area, the volume inside it is off by
+    /// the headspace and the shape of the neck
+    pub fn to_container(&self) -> Container {
+        Container {
+            volume: self.get_fill_volume(),
+            ..Container::from_dimensions(
+                self.get_shape(),
+                self.get_material(),
+                self.get_dimensions(),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::fixtures::assert_close;
+
+    #[test]
+    fn containers_hold_their_nominal_fill() {
+        let bottle = StandardContainer::NrwBottle.to_container();
+        assert_close(bottle.volume, milliliters_to_m3(500.0), 1e-9);
+    }
+
+    #[test]
+    fn outlines_are_close_to_the_nominal_fill() {
+        for container in StandardContainer::ALL {
+            let brimful = Container::from_dimensions(
+                container.get_shape(),
+                container.get_material(),
+                container.get_dimensions(),
+            )
+            .volume;
+            let ratio = brimful / container.get_fill_volume();
+            assert!((0.9..1.15).contains(&ratio), "{container:?}: {ratio}");
+        }
+    }
+}
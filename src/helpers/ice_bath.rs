@@ -0,0 +1,148 @@
+//! Ice baths, optionally salted
+//!
+//! Salt lowers the freezing point of the water, so ice melts and
+//! draws the heat for it from the bath until the bath sits at the
+//! freezing point of its brine. The melted ice dilutes the brine
+//! again, the temperature settles where both balance, but never
+//! below the eutectic point of salt water.
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    drink::{HEAT_CAPACITY_ICE, LATENT_HEAT_OF_FUSION_WATER},
+    fluid::{brine_freezing_point, EUTECTIC_TEMPERATURE},
+    Ambience, Fluid, Temperature, TemperatureUnit,
+};
+
+/// Heat capacity of water around 0 °C in J / (kg * K)
+const HEAT_CAPACITY_WATER: f64 = 4218.;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Recipe of an ice bath, all masses in kg
+pub struct IceBath {
+    pub ice_mass: f64,
+    pub water_mass: f64,
+    pub salt_mass: f64,
+}
+
+impl IceBath {
+    pub const fn new(ice_mass: f64, water_mass: f64, salt_mass: f64) -> Self {
+        IceBath {
+            ice_mass,
+            water_mass,
+            salt_mass,
+        }
+    }
+
+    pub fn with_salt(mut self, salt_mass: f64) -> Self {
+        self.salt_mass = salt_mass;
+        self
+    }
+
+    /// Ice in kg that has to melt to cool the bath down from 0 °C
+    /// to the given temperature in °C, at most all of it
+    fn melted_ice(&self, temperature: f64) -> f64 {
+        let heat_capacity =
+            self.water_mass * HEAT_CAPACITY_WATER + self.ice_mass * HEAT_CAPACITY_ICE;
+        (heat_capacity * -temperature / LATENT_HEAT_OF_FUSION_WATER).min(self.ice_mass)
+    }
+
+    /// Mass fraction of salt in the liquid at the given temperature in °C
+    fn salinity_at(&self, temperature: f64) -> f64 {
+        let liquid = self.water_mass + self.melted_ice(temperature) + self.salt_mass;
+        match liquid > 0. {
+            true => self.salt_mass / liquid,
+            false => 0.,
+        }
+    }
+
+    /// Temperature in °C the bath settles at, found by bisection
+    /// between 0 °C and the eutectic point
+    fn equilibrium(&self) -> f64 {
+        if self.salt_mass <= 0. {
+            return 0.;
+        }
+
+        let (mut warm, mut cold) = (0., EUTECTIC_TEMPERATURE);
+        for _ in 0..50 {
+            let temperature = (warm + cold) / 2.;
+            match brine_freezing_point(self.salinity_at(temperature)) < temperature {
+                true => warm = temperature,
+                false => cold = temperature,
+            }
+        }
+        warm
+    }
+
+    /// Mass fraction of salt in the liquid once the bath settled
+    pub fn salinity(&self) -> f64 {
+        self.salinity_at(self.equilibrium())
+    }
+
+    pub fn temperature(&self) -> Temperature {
+        Temperature::new_with_unit(self.equilibrium(), TemperatureUnit::DegCelsius)
+    }
+
+    pub fn fluid(&self) -> Fluid {
+        match self.salt_mass > 0. {
+            true => Fluid::Brine {
+                salinity: self.salinity(),
+            },
+            false => Fluid::Water,
+        }
+    }
+
+    /// Salt worth adding, about a tenth of the bath, in kg rounded
+    /// to 50 g
+    pub fn suggested_salt(&self) -> f64 {
+        ((self.ice_mass + self.water_mass) * 0.1 / 0.05)
+            .round()
+            .max(1.)
+            * 0.05
+    }
+
+    pub fn to_ambience(&self) -> Ambience {
+        let name = match self.salt_mass > 0. {
+            true => format!("Eisbad mit {:.0} g Salz", self.salt_mass * 1000.),
+            false => "Eisbad".to_string(),
+        };
+        let mut ambience = Ambience::new(
+            &name,
+            "./assets/images/flake.svg",
+            self.temperature(),
+            Some(self.fluid()),
+        );
+        ambience.ice_bath = Some(*self);
+        ambience
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{catalog, fixtures::assert_close, fluid::EUTECTIC_SALINITY};
+
+    #[test]
+    fn plain_ice_water_sits_at_zero() {
+        let bath = IceBath::new(1.5, 0.5, 0.);
+        assert_eq!(bath.temperature().as_deg_celsius(), 0.);
+        assert!(matches!(bath.fluid(), Fluid::Water));
+    }
+
+    #[test]
+    fn salted_bath_settles_where_melting_balances_the_brine() {
+        let bath = catalog::salted_ice_bath();
+        let temperature = bath.temperature.as_deg_celsius();
+        assert_close(temperature, -17.6, 0.1);
+
+        let recipe = bath.ice_bath.unwrap();
+        assert_close(brine_freezing_point(recipe.salinity()), temperature, 0.01);
+    }
+
+    #[test]
+    fn too_much_salt_stops_at_the_eutectic_point() {
+        let temperature = IceBath::new(1.5, 0.5, 2.).temperature().as_deg_celsius();
+        assert_close(temperature, brine_freezing_point(EUTECTIC_SALINITY), 0.01);
+        assert!(temperature >= EUTECTIC_TEMPERATURE);
+    }
+}
@@ -0,0 +1,264 @@
+//! Properties of water–ethanol mixtures
+//!
+//! Mixing water and ethanol is far from ideal: the volume contracts,
+//! heat capacity peaks above pure water at low concentrations and
+//! viscosity has a maximum around 50 % ABV. Properties are tabulated
+//! by alcohol by volume at around 20 °C and linearly interpolated.
+//!
+//! Dissolved sugar and extract are added on top: they make the drink
+//! denser, store less heat than water and lower the freezing point
+//! like any dilute solute, by `K_f * molality`.
+//!
+//! Sources, values rounded:
+//! - density: OIML alcoholometric tables
+//! - heat capacity, thermal conductivity, viscosity, freezing point:
+//!   <https://www.engineeringtoolbox.com/ethanol-water-d_989.html>,
+//!   converted from mass to volume fractions
+//! - sugar: sucrose solution tables, ICUMSA
+
+use super::{linear_interpolate, Temperature, TemperatureUnit};
+
+/// Heat transfer coefficient of natural convection inside a bottle
+/// of water, in W / (m^2 * K)
+const HEAT_TRANSFER_COEFFICIENT_WATER: f64 = 400.;
+
+/// Gravitational acceleration in m / s^2
+const GRAVITY: f64 = 9.81;
+
+/// Heat capacity of dissolved sugar in J / (kg * K)
+pub const HEAT_CAPACITY_SUGAR: f64 = 1250.;
+/// Cryoscopic constant of water in K * kg / mol
+const CRYOSCOPIC_CONSTANT_WATER: f64 = 1.86;
+/// Molar mass in kg / mol of glucose and fructose, most sugar in
+/// drinks is either of them or sucrose split into both by the acid
+const MOLAR_MASS_SUGAR: f64 = 0.180;
+/// Increase in density per kg / m^3 of dissolved sugar, the rest of
+/// its mass displaces liquid
+const DENSITY_INCREMENT_SUGAR: f64 = 0.38;
+
+/// One row of [`TABLE`]
+struct Properties {
+    /// Alcohol by volume, between 0. and 1.
+    abv: f64,
+    /// In kg / m^3
+    density: f64,
+    /// In J / (kg * K)
+    heat_capacity: f64,
+    /// In W / (m * K)
+    thermal_conductivity: f64,
+    /// Dynamic viscosity in mPa * s
+    viscosity: f64,
+    /// Volumetric thermal expansion coefficient in 1 / K, around 10 °C
+    thermal_expansion: f64,
+    /// In °C
+    freezing_point: f64,
+}
+
+const fn row(
+    abv: f64,
+    density: f64,
+    heat_capacity: f64,
+    thermal_conductivity: f64,
+    viscosity: f64,
+    thermal_expansion: f64,
+    freezing_point: f64,
+) -> Properties {
+    Properties {
+        abv,
+        density,
+        heat_capacity,
+        thermal_conductivity,
+        viscosity,
+        thermal_expansion,
+        freezing_point,
+    }
+}
+
+#[rustfmt::skip]
+const TABLE: [Properties; 12] = [
+    //   ABV   density   c_p    k      mu    beta     T_f
+    row(0.00,  998.2,  4182., 0.598, 1.00, 0.9e-4,    0.0),
+    row(0.05,  991.1,  4215., 0.575, 1.15, 1.0e-4,   -2.0),
+    row(0.10,  984.7,  4250., 0.550, 1.32, 1.3e-4,   -3.3),
+    row(0.20,  973.6,  4230., 0.500, 1.80, 2.5e-4,   -8.0),
+    row(0.30,  962.4,  4130., 0.450, 2.25, 4.0e-4,  -14.5),
+    row(0.40,  948.0,  3990., 0.410, 2.70, 5.5e-4,  -23.0),
+    row(0.50,  930.2,  3790., 0.370, 2.85, 6.5e-4,  -31.0),
+    row(0.60,  909.1,  3570., 0.330, 2.75, 7.5e-4,  -39.5),
+    row(0.70,  886.0,  3330., 0.290, 2.45, 8.5e-4,  -48.0),
+    row(0.80,  861.5,  3060., 0.250, 2.05, 9.5e-4,  -59.0),
+    row(0.90,  833.8,  2750., 0.210, 1.65, 1.0e-3,  -73.0),
+    row(1.00,  789.3,  2440., 0.167, 1.20, 1.1e-3, -114.0),
+];
+
+#[derive(Debug, Clone, Copy)]
+/// Liquid of a drink, water with the given share of ethanol
+/// and dissolved sugar
+pub struct Mixture {
+    /// Alcohol by volume, between 0. and 1.
+    pub alcohol_percentage: f64,
+    /// Dissolved sugar and extract in kg / m^3, the same as g / l
+    pub sugar_content: f64,
+}
+
+impl Mixture {
+    pub fn new(alcohol_percentage: f64) -> Self {
+        Mixture {
+            alcohol_percentage: alcohol_percentage.clamp(0., 1.),
+            sugar_content: 0.,
+        }
+    }
+
+    pub fn with_sugar(mut self, sugar_content: f64) -> Self {
+        self.sugar_content = sugar_content.max(0.);
+        self
+    }
+
+    /// Interpolates one property between the neighbouring rows
+    fn interpolate(&self, property: impl Fn(&Properties) -> f64) -> f64 {
+        let abv = self.alcohol_percentage;
+        let upper = TABLE
+            .iter()
+            .position(|row| row.abv >= abv)
+            .unwrap_or(TABLE.len() - 1)
+            .max(1);
+        let (low, high) = (&TABLE[upper - 1], &TABLE[upper]);
+
+        linear_interpolate(abv, low.abv, high.abv, property(low), property(high))
+    }
+
+    /// In kg / m^3, lower than the average of both liquids
+    /// weighted by volume because the mixture contracts
+    pub fn density(&self) -> f64 {
+        self.interpolate(|row| row.density) + DENSITY_INCREMENT_SUGAR * self.sugar_content
+    }
+
+    /// Share of ethanol in the mass of the mixture
+    pub fn ethanol_mass_fraction(&self) -> f64 {
+        self.alcohol_percentage * TABLE[TABLE.len() - 1].density / self.density()
+    }
+
+    /// Share of sugar in the mass of the mixture, ° Brix / 100
+    pub fn sugar_mass_fraction(&self) -> f64 {
+        self.sugar_content / self.density()
+    }
+
+    /// Share of water in the mass of the mixture
+    pub fn water_mass_fraction(&self) -> f64 {
+        1. - self.ethanol_mass_fraction() - self.sugar_mass_fraction()
+    }
+
+    /// In J / (kg * K), sugar mixed in by mass
+    pub fn heat_capacity(&self) -> f64 {
+        let sugar = self.sugar_mass_fraction();
+        (1. - sugar) * self.interpolate(|row| row.heat_capacity) + sugar * HEAT_CAPACITY_SUGAR
+    }
+
+    /// In W / (m * K)
+    pub fn thermal_conductivity(&self) -> f64 {
+        self.interpolate(|row| row.thermal_conductivity)
+    }
+
+    /// Dynamic viscosity in Pa * s
+    pub fn viscosity(&self) -> f64 {
+        self.interpolate(|row| row.viscosity) / 1000.
+    }
+
+    /// Volumetric thermal expansion coefficient in 1 / K
+    pub fn thermal_expansion(&self) -> f64 {
+        self.interpolate(|row| row.thermal_expansion)
+    }
+
+    /// Freezing point of the water–ethanol mixture, lowered further
+    /// by the molality of the sugar in the water
+    pub fn freezing_point(&self) -> Temperature {
+        // Water -- kg / m^3
+        let water = self.density() * self.water_mass_fraction();
+        // Molality -- mol / kg
+        let molality = self.sugar_content / MOLAR_MASS_SUGAR / water;
+
+        Temperature::new_with_unit(
+            self.interpolate(|row| row.freezing_point) - CRYOSCOPIC_CONSTANT_WATER * molality,
+            TemperatureUnit::DegCelsius,
+        )
+    }
+
+    /// Heat transfer coefficient of natural convection inside the
+    /// container, in W / (m^2 * K)
+    ///
+    /// Scaled from water with the laminar correlation Nu ~ Ra^(1/4),
+    /// which gives h ~ k^(3/4) * (rho^2 * c_p * beta / mu)^(1/4).
+    pub fn heat_transfer_coefficient(&self) -> f64 {
+        let convection = |mixture: &Mixture| {
+            mixture.thermal_conductivity().powf(0.75)
+                * (mixture.density().powi(2)
+                    * mixture.heat_capacity()
+                    * mixture.thermal_expansion()
+                    / mixture.viscosity())
+                .powf(0.25)
+        };
+
+        HEAT_TRANSFER_COEFFICIENT_WATER * convection(self) / convection(&Mixture::new(0.))
+    }
+
+    /// Ratio of how fast momentum and heat spread in the liquid
+    pub fn prandtl_number(&self) -> f64 {
+        self.viscosity() * self.heat_capacity() / self.thermal_conductivity()
+    }
+
+    /// How strongly natural convection stirs the liquid across the
+    /// given length in m with the given temperature difference in K
+    pub fn rayleigh_number(&self, length: f64, temperature_difference: f64) -> f64 {
+        let kinematic_viscosity = self.viscosity() / self.density();
+        let thermal_diffusivity =
+            self.thermal_conductivity() / (self.density() * self.heat_capacity());
+
+        GRAVITY * self.thermal_expansion() * temperature_difference.abs() * length.powi(3)
+            / (kinematic_viscosity * thermal_diffusivity)
+    }
+}
+
+/// Factor by which natural convection raises the thermal conductivity
+/// of liquid in an enclosure, Catton's correlation, never below 1.
+pub fn convection_factor(rayleigh_number: f64, prandtl_number: f64) -> f64 {
+    (0.18 * (prandtl_number / (0.2 + prandtl_number) * rayleigh_number).powf(0.29)).max(1.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::fixtures::assert_close;
+
+    #[test]
+    fn rows_are_taken_as_tabulated() {
+        let beer = Mixture::new(0.05);
+        assert_close(beer.density(), 991.1, 1e-9);
+        assert_close(beer.heat_capacity(), 4215., 1e-9);
+        assert_close(beer.freezing_point().as_deg_celsius(), -2.0, 1e-9);
+    }
+
+    #[test]
+    fn properties_are_interpolated_between_rows() {
+        let mixture = Mixture::new(0.075);
+        assert_close(mixture.density(), (991.1 + 984.7) / 2., 1e-9);
+        assert_close(mixture.freezing_point().as_deg_celsius(), -2.65, 1e-9);
+    }
+
+    #[test]
+    fn sugar_lowers_the_freezing_point_by_its_molality() {
+        // 100 g / l in 936.2 kg of water per m^3 is 0.593 mol / kg
+        let lemonade = Mixture::new(0.).with_sugar(100.);
+        assert_close(lemonade.freezing_point().as_deg_celsius(), -1.104, 1e-3);
+        assert_close(lemonade.density(), 998.2 + 38., 1e-9);
+    }
+
+    #[test]
+    fn water_keeps_the_reference_heat_transfer() {
+        assert_close(
+            Mixture::new(0.).heat_transfer_coefficient(),
+            HEAT_TRANSFER_COEFFICIENT_WATER,
+            1e-9,
+        );
+        assert!(Mixture::new(0.4).heat_transfer_coefficient() < HEAT_TRANSFER_COEFFICIENT_WATER);
+    }
+}
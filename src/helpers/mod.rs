@@ -1,16 +1,113 @@
 #![allow(unused)]
+#[cfg(feature = "web")]
+pub mod alarm;
 pub mod ambience;
+pub mod batch;
+pub mod calibration;
+pub mod catalog;
 pub mod drink;
+pub mod evaporation;
+#[cfg(test)]
+pub mod fixtures;
+pub mod fluid;
+pub mod geometry;
+pub mod ice_bath;
+pub mod mixture;
+pub mod model;
+pub mod phase;
+pub mod plan;
+pub mod segment;
+pub mod serving;
+#[cfg(feature = "web")]
+pub mod storage;
+#[cfg(feature = "web")]
 pub mod timer_info;
 pub mod timer_preset;
+pub mod validity;
+
+use std::fmt;
 
 pub use ambience::Ambience;
 use chrono::Duration;
-pub use drink::{Drink, Fluid, Temperature, TemperatureUnit};
+use drink::HeatTransferCoefficient;
+pub use drink::{Drink, Temperature, TemperatureUnit};
+use evaporation::evaporation;
+pub use fluid::Fluid;
+pub use phase::Phase;
 pub use timer_preset::TimerPreset;
 
+#[derive(Debug, Clone, Copy)]
+/// Why the cooling of a drink can't be predicted
+pub enum CoolingError {
+    /// The fluid of the ambience can't surround a drink at the
+    /// temperature of the ambience, e.g. water below 0 °C
+    FluidOutOfRange {
+        fluid: Fluid,
+        temperature: Temperature,
+    },
+    /// The drink has no heat capacity or no surface to cool through
+    InvalidDrink,
+    /// The target temperature doesn't lie between the initial
+    /// temperature and the one the drink tends to in the ambience
+    TargetUnreachable,
+    /// The target temperature is not reached within the time a
+    /// simulating model looks ahead
+    BeyondHorizon,
+}
+
+impl CoolingError {
+    pub fn get_description(&self) -> &'static str {
+        match self {
+            CoolingError::FluidOutOfRange { .. } => {
+                "Das Kühlmedium kann diese Temperatur nicht haben"
+            }
+            CoolingError::InvalidDrink => "Das Getränk hat kein Volumen",
+            CoolingError::TargetUnreachable => {
+                "Zieltemperatur muss zwischen Ausgangs- und Umgebungstemperatur liegen"
+            }
+            CoolingError::BeyondHorizon => "Zieltemperatur wird erst nach Tagen erreicht",
+        }
+    }
+}
+
+impl fmt::Display for CoolingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoolingError::FluidOutOfRange { fluid, temperature } => {
+                let range = fluid.temperature_range();
+                write!(
+                    f,
+                    "{} can't be at {:.1} °C, only between {} °C and {} °C",
+                    fluid.get_name(),
+                    temperature.as_deg_celsius(),
+                    range.start(),
+                    range.end()
+                )
+            }
+            CoolingError::InvalidDrink => {
+                write!(f, "the drink has no heat capacity or surface area")
+            }
+            CoolingError::TargetUnreachable => {
+                write!(
+                    f,
+                    "the target temperature isn't between the initial and the ambient temperature"
+                )
+            }
+            CoolingError::BeyondHorizon => {
+                write!(f, "the target temperature isn't reached within days")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CoolingError {}
+
 /// How long does it take until a drink reaches its
-/// target temperature
+/// target temperature, cooling down or warming up
+///
+/// Targets below the freezing point of the drink include the time
+/// it takes to freeze the whole drink, targets above it for a frozen
+/// drink the time it takes to thaw.
 ///
 /// Reverse function of [`temperature_after_time`]
 pub fn time_until_temperature(
@@ -18,50 +115,346 @@ pub fn time_until_temperature(
     initial_temperature: Temperature,
     drink: &Drink,
     ambience: &Ambience,
-) -> Duration {
-    let cooling_coefficient = match ambience.fluid {
-        Fluid::Air => drink.cooling_coefficient.0,
-        Fluid::Water => drink.cooling_coefficient.1,
-        _ => todo!("no cooling coefficient for other fluids than water and air"),
-    };
+) -> Result<Duration, CoolingError> {
+    let cooling_coefficient = cooling_coefficient(drink, ambience)?;
+    let sink = sink_temperature(drink, ambience);
+    if !target_reachable(target_temperature, initial_temperature, sink) {
+        return Err(CoolingError::TargetUnreachable);
+    }
 
-    let gradient =
-        (target_temperature - ambience.temperature) / (initial_temperature - ambience.temperature);
+    let below_freezing_point = target_temperature.as_kelvin() < drink.freezing_point.as_kelvin();
+    let t = if starts_frozen(initial_temperature, drink) {
+        match seconds_until_thawing(initial_temperature, drink, ambience)? {
+            Some(thawing) if !below_freezing_point => {
+                let gradient = (target_temperature - sink) / (drink.freezing_point - sink);
+
+                thawing + seconds_to_freeze(drink, ambience)?
+                    - f64::log(gradient, std::f64::consts::E) / cooling_coefficient
+            }
+            _ => {
+                let gradient = (target_temperature - sink) / (initial_temperature - sink);
 
-    let t = -f64::log(gradient, std::f64::consts::E) / cooling_coefficient;
+                -f64::log(gradient, std::f64::consts::E)
+                    / frozen_cooling_coefficient(drink, ambience)?
+            }
+        }
+    } else {
+        match seconds_until_freezing(initial_temperature, drink, ambience)? {
+            Some(freezing) if below_freezing_point => {
+                let gradient = (target_temperature - sink) / (drink.freezing_point - sink);
 
-    Duration::milliseconds(t as i64 * 1000)
+                freezing + seconds_to_freeze(drink, ambience)?
+                    - f64::log(gradient, std::f64::consts::E)
+                        / frozen_cooling_coefficient(drink, ambience)?
+            }
+            _ => {
+                let gradient = (target_temperature - sink) / (initial_temperature - sink);
+
+                -f64::log(gradient, std::f64::consts::E) / cooling_coefficient
+            }
+        }
+    };
+
+    Ok(duration_from_seconds(t))
 }
 
 /// Calculates the current temperature the drink has
 /// when it has been in the freezer/fridge for specified time
 ///
+/// Once the drink reaches its freezing point, its temperature stays
+/// there until all of its water is frozen, or thawed for a frozen
+/// drink that warms up.
+///
 /// Reverse function of [`time_until_temperature`]
 pub fn temperature_after_time(
     time: Duration,
     initial_temperature: Temperature,
     drink: &Drink,
     ambience: &Ambience,
-) -> Temperature {
-    let cooling_coefficient = match ambience.fluid {
-        Fluid::Air => drink.cooling_coefficient.0,
-        Fluid::Water => drink.cooling_coefficient.1,
-        _ => todo!("no cooling coefficient for other fluids than water and air"),
+) -> Result<Temperature, CoolingError> {
+    let time = time.num_milliseconds() as f64 / 1000.;
+
+    if starts_frozen(initial_temperature, drink) {
+        let Some(thawing) = seconds_until_thawing(initial_temperature, drink, ambience)? else {
+            return Ok(exponential_cooling(
+                time,
+                initial_temperature,
+                sink_temperature(drink, ambience),
+                frozen_cooling_coefficient(drink, ambience)?,
+            ));
+        };
+
+        let thawed = thawing + seconds_to_freeze(drink, ambience)?;
+
+        let temperature = if time < thawing {
+            exponential_cooling(
+                time,
+                initial_temperature,
+                sink_temperature(drink, ambience),
+                frozen_cooling_coefficient(drink, ambience)?,
+            )
+        } else if time < thawed {
+            drink.freezing_point
+        } else {
+            exponential_cooling(
+                time - thawed,
+                drink.freezing_point,
+                sink_temperature(drink, ambience),
+                cooling_coefficient(drink, ambience)?,
+            )
+        };
+
+        return Ok(temperature);
+    }
+
+    let Some(freezing) = seconds_until_freezing(initial_temperature, drink, ambience)? else {
+        return Ok(exponential_cooling(
+            time,
+            initial_temperature,
+            sink_temperature(drink, ambience),
+            cooling_coefficient(drink, ambience)?,
+        ));
+    };
+
+    let frozen = freezing + seconds_to_freeze(drink, ambience)?;
+
+    let temperature = if time < freezing {
+        exponential_cooling(
+            time,
+            initial_temperature,
+            sink_temperature(drink, ambience),
+            cooling_coefficient(drink, ambience)?,
+        )
+    } else if time < frozen {
+        drink.freezing_point
+    } else {
+        exponential_cooling(
+            time - frozen,
+            drink.freezing_point,
+            sink_temperature(drink, ambience),
+            frozen_cooling_coefficient(drink, ambience)?,
+        )
+    };
+
+    Ok(temperature)
+}
+
+/// How long does it take until ice starts to form in the drink
+///
+/// Returns `None` if the ambience is not cold enough to ever
+/// freeze the drink.
+pub fn time_until_freezing(
+    initial_temperature: Temperature,
+    drink: &Drink,
+    ambience: &Ambience,
+) -> Result<Option<Duration>, CoolingError> {
+    Ok(seconds_until_freezing(initial_temperature, drink, ambience)?.map(duration_from_seconds))
+}
+
+/// How long does it take until all of the water in the drink
+/// is frozen, the point where glass bottles burst
+///
+/// Returns `None` if the ambience is not cold enough to ever
+/// freeze the drink.
+pub fn time_until_frozen(
+    initial_temperature: Temperature,
+    drink: &Drink,
+    ambience: &Ambience,
+) -> Result<Option<Duration>, CoolingError> {
+    let Some(freezing) = seconds_until_freezing(initial_temperature, drink, ambience)? else {
+        return Ok(None);
+    };
+    if starts_frozen(initial_temperature, drink) {
+        return Ok(Some(Duration::zero()));
+    }
+
+    Ok(Some(duration_from_seconds(
+        freezing + seconds_to_freeze(drink, ambience)?,
+    )))
+}
+
+/// In which state the drink is after it has been in the
+/// freezer/fridge for specified time
+pub fn phase_after_time(
+    time: Duration,
+    initial_temperature: Temperature,
+    drink: &Drink,
+    ambience: &Ambience,
+) -> Result<Phase, CoolingError> {
+    if starts_frozen(initial_temperature, drink) {
+        let Some(thawing) = seconds_until_thawing(initial_temperature, drink, ambience)? else {
+            return Ok(Phase::Frozen);
+        };
+
+        let time = time.num_milliseconds() as f64 / 1000.;
+        let thawed_fraction = (time - thawing) / seconds_to_freeze(drink, ambience)?;
+
+        return Ok(match thawed_fraction {
+            f if f < 0. => Phase::Frozen,
+            f if f < 1. => Phase::Freezing(1. - f),
+            _ => Phase::Liquid,
+        });
+    }
+
+    let Some(freezing) = seconds_until_freezing(initial_temperature, drink, ambience)? else {
+        return Ok(Phase::Liquid);
     };
 
     let time = time.num_milliseconds() as f64 / 1000.;
-    let temperature = ambience.temperature.as_kelvin()
-        + (initial_temperature.as_kelvin() - ambience.temperature.as_kelvin())
+    let frozen_fraction = (time - freezing) / seconds_to_freeze(drink, ambience)?;
+
+    Ok(match frozen_fraction {
+        f if f < 0. => Phase::Liquid,
+        f if f < 1. => Phase::Freezing(f),
+        _ => Phase::Frozen,
+    })
+}
+
+/// Whether a drink starting at the initial temperature ever
+/// reaches the target temperature in the given ambience
+pub fn target_reachable(
+    target_temperature: Temperature,
+    initial_temperature: Temperature,
+    ambient_temperature: Temperature,
+) -> bool {
+    let gradient =
+        (target_temperature - ambient_temperature) / (initial_temperature - ambient_temperature);
+
+    gradient > 0. && gradient < 1.
+}
+
+/// Whether a drink warms up to the target temperature, e.g. red
+/// wine taken out of the fridge, instead of cooling down to it
+pub fn is_warming(initial_temperature: Temperature, target_temperature: Temperature) -> bool {
+    target_temperature.as_kelvin() > initial_temperature.as_kelvin()
+}
+
+/// Temperature the drink cools towards, below the ambience if
+/// water evaporates from it
+pub fn sink_temperature(drink: &Drink, ambience: &Ambience) -> Temperature {
+    evaporation(drink, ambience).map_or(ambience.temperature, |evaporation| {
+        evaporation.sink_temperature
+    })
+}
+
+/// Cooling coefficient of the drink in the fluid of the ambience,
+/// moved as the ambience says and with evaporation, in 1 / s
+///
+/// A calibration of the drink in the same ambience replaces the
+/// calculated value.
+pub fn cooling_coefficient(drink: &Drink, ambience: &Ambience) -> Result<f64, CoolingError> {
+    if !ambience.fluid.allows_temperature(ambience.temperature) {
+        return Err(CoolingError::FluidOutOfRange {
+            fluid: ambience.fluid,
+            temperature: ambience.temperature,
+        });
+    }
+    if !(drink.heat_capacity > 0. && drink.container.surface_area > 0.) {
+        return Err(CoolingError::InvalidDrink);
+    }
+
+    match drink.calibrated_cooling_coefficient(ambience) {
+        Some(cooling_coefficient) => Ok(cooling_coefficient),
+        None => Ok(drink.cooling_coefficient(outer_heat_transfer_coefficient(drink, ambience))),
+    }
+}
+
+/// Heat transfer coefficient from the ambience to the outside of
+/// the container, with evaporation, in W / (m^2 * K)
+pub fn outer_heat_transfer_coefficient(drink: &Drink, ambience: &Ambience) -> f64 {
+    let evaporation = evaporation(drink, ambience)
+        .map_or(0., |evaporation| evaporation.heat_transfer_coefficient);
+    ambience.heat_transfer_coefficient() + evaporation
+}
+
+/// Cooling coefficient of the drink once its water is frozen, the
+/// heat transfer stays the same but less heat is stored per kelvin
+fn frozen_cooling_coefficient(drink: &Drink, ambience: &Ambience) -> Result<f64, CoolingError> {
+    Ok(cooling_coefficient(drink, ambience)? * drink.heat_capacity / drink.heat_capacity_frozen)
+}
+
+/// Whether the drink is frozen solid at the initial temperature, a
+/// drink stored below its freezing point is taken to be all ice
+fn starts_frozen(initial_temperature: Temperature, drink: &Drink) -> bool {
+    initial_temperature.as_kelvin() < drink.freezing_point.as_kelvin()
+}
+
+/// Seconds until the drink reaches its freezing point, a drink that
+/// starts at or below it is assumed to start freezing right away
+fn seconds_until_freezing(
+    initial_temperature: Temperature,
+    drink: &Drink,
+    ambience: &Ambience,
+) -> Result<Option<f64>, CoolingError> {
+    let cooling_coefficient = cooling_coefficient(drink, ambience)?;
+    if sink_temperature(drink, ambience).as_kelvin() >= drink.freezing_point.as_kelvin() {
+        return Ok(None);
+    }
+    if initial_temperature.as_kelvin() <= drink.freezing_point.as_kelvin() {
+        return Ok(Some(0.));
+    }
+
+    let gradient = (drink.freezing_point - sink_temperature(drink, ambience))
+        / (initial_temperature - sink_temperature(drink, ambience));
+
+    Ok(Some(
+        -f64::log(gradient, std::f64::consts::E) / cooling_coefficient,
+    ))
+}
+
+/// Seconds until a frozen drink warms up to its freezing point and
+/// starts to thaw
+///
+/// Returns `None` if the drink isn't frozen or the ambience is not
+/// warm enough to ever thaw it.
+fn seconds_until_thawing(
+    initial_temperature: Temperature,
+    drink: &Drink,
+    ambience: &Ambience,
+) -> Result<Option<f64>, CoolingError> {
+    let frozen_cooling_coefficient = frozen_cooling_coefficient(drink, ambience)?;
+    if !starts_frozen(initial_temperature, drink)
+        || sink_temperature(drink, ambience).as_kelvin() <= drink.freezing_point.as_kelvin()
+    {
+        return Ok(None);
+    }
+
+    let gradient = (drink.freezing_point - sink_temperature(drink, ambience))
+        / (initial_temperature - sink_temperature(drink, ambience));
+
+    Ok(Some(
+        -f64::log(gradient, std::f64::consts::E) / frozen_cooling_coefficient,
+    ))
+}
+
+/// Seconds it takes to remove the latent heat of the drink while it
+/// sits at its freezing point, or to add it back when it thaws
+fn seconds_to_freeze(drink: &Drink, ambience: &Ambience) -> Result<f64, CoolingError> {
+    // Heat flow -- W
+    let heat_flow = cooling_coefficient(drink, ambience)?
+        * drink.heat_capacity
+        * (drink.freezing_point - sink_temperature(drink, ambience))
+            .as_kelvin()
+            .abs();
+
+    Ok(drink.latent_heat / heat_flow)
+}
+
+fn exponential_cooling(
+    time: f64,
+    initial_temperature: Temperature,
+    ambient_temperature: Temperature,
+    cooling_coefficient: f64,
+) -> Temperature {
+    let temperature = ambient_temperature.as_kelvin()
+        + (initial_temperature.as_kelvin() - ambient_temperature.as_kelvin())
             * f64::exp(-cooling_coefficient * time);
 
     Temperature::new(temperature)
 }
 
-fn calculate_freezing_point(alcohol: f64) -> Temperature {
-    // https://www.engineeringtoolbox.com/ethanol-water-d_989.html
-    let t = linear_interpolate(alcohol, 0.0, 0.6, 0.0, -37.0);
-
-    Temperature::new_with_unit(t, TemperatureUnit::DegCelsius)
+fn duration_from_seconds(seconds: f64) -> Duration {
+    Duration::milliseconds((seconds * 1000.) as i64)
 }
 
 pub fn linear_interpolate_ceil(x: f64, x0: f64, x1: f64, y0: f64, y1: f64) -> f64 {
@@ -78,7 +471,7 @@ pub fn milliliters_to_m3(milliliters: f64) -> f64 {
 
 pub fn format_chrono_duration(duration: Duration) -> String {
     if duration <= Duration::zero() {
-        return format!("0:00:00");
+        return "0:00:00".to_string();
     }
     let seconds = duration.num_seconds() % 60;
     let minutes = duration.num_minutes() % 60;
@@ -93,7 +486,7 @@ pub fn format_chrono_duration(duration: Duration) -> String {
 
 pub fn format_chrono_duration_precise(duration: Duration) -> String {
     if duration <= Duration::zero() {
-        return format!("0:00");
+        return "0:00".to_string();
     }
     let seconds = duration.num_seconds() % 60;
     let minutes = duration.num_minutes() % 60;
@@ -108,7 +501,7 @@ pub fn format_chrono_duration_precise(duration: Duration) -> String {
 
 pub fn format_chrono_duration_simple(duration: Duration) -> String {
     if duration <= Duration::zero() {
-        return format!("0:00:00");
+        return "0:00:00".to_string();
     }
     let seconds = duration.num_seconds() % 60;
     let minutes = duration.num_minutes() % 60;
@@ -116,3 +509,61 @@ pub fn format_chrono_duration_simple(duration: Duration) -> String {
 
     format!("{:0>1}:{:0>2}", hours, minutes)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::fixtures::{assert_close, celsius};
+    use super::*;
+
+    /// Temperature after the time the drink needs to reach the target
+    fn round_trip(target: f64, initial: f64, drink: &Drink, ambience: &Ambience) -> f64 {
+        let time =
+            time_until_temperature(celsius(target), celsius(initial), drink, ambience).unwrap();
+        temperature_after_time(time, celsius(initial), drink, ambience)
+            .unwrap()
+            .as_deg_celsius()
+    }
+
+    #[test]
+    fn cooling_round_trips() {
+        let beer = catalog::beer_500_glass();
+        assert_close(round_trip(7., 20., &beer, &catalog::freezer()), 7., 0.01);
+    }
+
+    #[test]
+    fn freezing_round_trips_past_the_plateau() {
+        let beer = catalog::beer_500_glass();
+        let freezer = catalog::freezer();
+        assert_close(round_trip(-10., 20., &beer, &freezer), -10., 0.01);
+
+        // Halfway through freezing the drink sits at its freezing point
+        let freezing = time_until_freezing(celsius(20.), &beer, &freezer)
+            .unwrap()
+            .unwrap();
+        let frozen = time_until_frozen(celsius(20.), &beer, &freezer)
+            .unwrap()
+            .unwrap();
+        let halfway =
+            temperature_after_time((freezing + frozen) / 2, celsius(20.), &beer, &freezer).unwrap();
+        assert_eq!(halfway.as_kelvin(), beer.freezing_point.as_kelvin());
+    }
+
+    #[test]
+    fn warming_round_trips() {
+        let wine = catalog::red_wine_750_glass();
+        let room = catalog::room_temperature();
+        assert_close(round_trip(16., 5., &wine, &room), 16., 0.01);
+    }
+
+    #[test]
+    fn thawing_round_trips_past_the_plateau() {
+        let beer = catalog::beer_500_glass();
+        let room = catalog::room_temperature();
+        assert_close(round_trip(8., -18., &beer, &room), 8., 0.01);
+        assert_close(round_trip(-5., -18., &beer, &room), -5., 0.01);
+        assert_eq!(
+            phase_after_time(Duration::minutes(1), celsius(-18.), &beer, &room).unwrap(),
+            Phase::Frozen
+        );
+    }
+}
@@ -0,0 +1,882 @@
+//! Models predicting how the temperature of a drink changes
+//!
+//! [`Exponential`] treats the drink as one well mixed lump with a
+//! fixed heat transfer coefficient inside, quick and good enough
+//! for small drinks. [`FiniteDifference`] splits the liquid into
+//! shells from the wall to the core, so the core lags behind the
+//! wall like it does in large bottles, while natural convection
+//! mixes the liquid the more the core and the wall differ.
+//!
+//! Temperatures are those of the drink once poured and mixed.
+
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+use super::{
+    cooling_coefficient, duration_from_seconds,
+    mixture::{convection_factor, Mixture},
+    outer_heat_transfer_coefficient, sink_temperature, target_reachable,
+    validity::{validity, ConfidenceBand},
+    Ambience, CoolingError, Drink, Phase, Temperature,
+};
+
+/// Predicts the temperature and state of a drink in one ambience
+pub trait CoolingModel {
+    /// Temperature of the drink after it has been in the ambience
+    /// for the given time
+    fn temperature_after_time(
+        &self,
+        time: Duration,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Temperature, CoolingError>;
+
+    /// State of the water in the drink after the given time
+    fn phase_after_time(
+        &self,
+        time: Duration,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Phase, CoolingError>;
+
+    /// How long it takes until the drink reaches the target temperature
+    fn time_until_temperature(
+        &self,
+        target_temperature: Temperature,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Duration, CoolingError>;
+
+    /// [`CoolingModel::time_until_temperature`] with the range it
+    /// likely falls into, see [`crate::helpers::validity`]
+    fn time_until_temperature_band(
+        &self,
+        target_temperature: Temperature,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<ConfidenceBand<Duration>, CoolingError> {
+        let expected =
+            self.time_until_temperature(target_temperature, initial_temperature, drink, ambience)?;
+        let uncertainty =
+            validity(initial_temperature, drink, ambience)?.uncertainty(self.resolves_gradients());
+        Ok(ConfidenceBand::new(expected, uncertainty))
+    }
+
+    /// Whether the model lets the core of the drink lag behind the wall
+    fn resolves_gradients(&self) -> bool {
+        false
+    }
+
+    /// How long it takes until ice starts to form, `None` if the
+    /// ambience is not cold enough to ever freeze the drink
+    fn time_until_freezing(
+        &self,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Option<Duration>, CoolingError>;
+
+    /// How long it takes until all of the water is frozen, `None`
+    /// if the ambience is not cold enough to ever freeze the drink
+    fn time_until_frozen(
+        &self,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Option<Duration>, CoolingError>;
+
+    /// Temperatures and states over the given time, to look up
+    /// instead of predicting them again and again
+    fn trajectory(
+        &self,
+        span: Duration,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Trajectory, CoolingError> {
+        let samples = sample_times(seconds(span))
+            .map(|time| {
+                let duration = duration_from_seconds(time);
+                Ok((
+                    time,
+                    self.temperature_after_time(duration, initial_temperature, drink, ambience)?
+                        .as_kelvin(),
+                    self.phase_after_time(duration, initial_temperature, drink, ambience)?,
+                ))
+            })
+            .collect::<Result<_, CoolingError>>()?;
+        Ok(Trajectory { samples })
+    }
+}
+
+/// Seconds since the start, temperature in K and state of the drink
+type Sample = (f64, f64, Phase);
+
+#[derive(Debug, Clone, PartialEq)]
+/// Predicted course of a drink in one ambience, sampled every
+/// [`SAMPLE_INTERVAL`] and at its end
+pub struct Trajectory {
+    samples: Vec<Sample>,
+}
+
+impl Trajectory {
+    /// Temperature at the end of the predicted time
+    pub fn last_temperature(&self) -> Temperature {
+        let (_, temperature, _) = self.samples[self.samples.len() - 1];
+        Temperature::new(temperature)
+    }
+
+    /// Temperature after the given time, linearly interpolated
+    /// between the samples
+    ///
+    /// Returns `None` past the predicted time.
+    pub fn temperature_after_time(&self, time: Duration) -> Option<Temperature> {
+        let ((_, before, _), (_, after, _), fraction) = self.neighbours(time)?;
+        Some(Temperature::new(before + (after - before) * fraction))
+    }
+
+    /// State after the given time, that of the sample before unless
+    /// the drink is freezing at both
+    ///
+    /// Returns `None` past the predicted time.
+    pub fn phase_after_time(&self, time: Duration) -> Option<Phase> {
+        let ((.., before), (.., after), fraction) = self.neighbours(time)?;
+        Some(match (before, after) {
+            (Phase::Freezing(before), Phase::Freezing(after)) => {
+                Phase::Freezing(before + (after - before) * fraction)
+            }
+            (before, _) => before,
+        })
+    }
+
+    /// Samples around the given time and how far it is between them
+    fn neighbours(&self, time: Duration) -> Option<(Sample, Sample, f64)> {
+        let time = seconds(time).max(0.);
+        let index = self.samples.partition_point(|(sample, ..)| *sample < time);
+        let after = *self.samples.get(index)?;
+        if index == 0 || after.0 == time {
+            return Some((after, after, 0.));
+        }
+
+        let before = self.samples[index - 1];
+        Some((before, after, (time - before.0) / (after.0 - before.0)))
+    }
+}
+
+/// Seconds since the start a [`Trajectory`] over the given span is
+/// sampled at
+fn sample_times(span: f64) -> impl Iterator<Item = f64> {
+    let intervals = (span.max(0.) / SAMPLE_INTERVAL).ceil() as usize;
+    (0..intervals)
+        .map(|interval| interval as f64 * SAMPLE_INTERVAL)
+        .chain(std::iter::once(span.max(0.)))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+/// Model the user picked, see the module documentation
+pub enum CoolingModelKind {
+    #[default]
+    Exponential,
+    FiniteDifference,
+}
+
+impl CoolingModelKind {
+    pub const ALL: [CoolingModelKind; 2] = [Self::Exponential, Self::FiniteDifference];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Exponential => "Einfach",
+            Self::FiniteDifference => "Genau",
+        }
+    }
+
+    fn model(&self) -> &'static dyn CoolingModel {
+        match self {
+            Self::Exponential => &Exponential,
+            Self::FiniteDifference => &FiniteDifference { shells: 8 },
+        }
+    }
+}
+
+impl CoolingModel for CoolingModelKind {
+    fn temperature_after_time(
+        &self,
+        time: Duration,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Temperature, CoolingError> {
+        self.model()
+            .temperature_after_time(time, initial_temperature, drink, ambience)
+    }
+
+    fn phase_after_time(
+        &self,
+        time: Duration,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Phase, CoolingError> {
+        self.model()
+            .phase_after_time(time, initial_temperature, drink, ambience)
+    }
+
+    fn time_until_temperature(
+        &self,
+        target_temperature: Temperature,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Duration, CoolingError> {
+        self.model().time_until_temperature(
+            target_temperature,
+            initial_temperature,
+            drink,
+            ambience,
+        )
+    }
+
+    fn time_until_freezing(
+        &self,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Option<Duration>, CoolingError> {
+        self.model()
+            .time_until_freezing(initial_temperature, drink, ambience)
+    }
+
+    fn time_until_frozen(
+        &self,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Option<Duration>, CoolingError> {
+        self.model()
+            .time_until_frozen(initial_temperature, drink, ambience)
+    }
+
+    fn resolves_gradients(&self) -> bool {
+        self.model().resolves_gradients()
+    }
+
+    fn trajectory(
+        &self,
+        span: Duration,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Trajectory, CoolingError> {
+        self.model()
+            .trajectory(span, initial_temperature, drink, ambience)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Newton's law of cooling with the lumped cooling coefficient, the
+/// functions in [`crate::helpers`] are this model
+pub struct Exponential;
+
+impl CoolingModel for Exponential {
+    fn temperature_after_time(
+        &self,
+        time: Duration,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Temperature, CoolingError> {
+        super::temperature_after_time(time, initial_temperature, drink, ambience)
+    }
+
+    fn phase_after_time(
+        &self,
+        time: Duration,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Phase, CoolingError> {
+        super::phase_after_time(time, initial_temperature, drink, ambience)
+    }
+
+    fn time_until_temperature(
+        &self,
+        target_temperature: Temperature,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Duration, CoolingError> {
+        super::time_until_temperature(target_temperature, initial_temperature, drink, ambience)
+    }
+
+    fn time_until_freezing(
+        &self,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Option<Duration>, CoolingError> {
+        super::time_until_freezing(initial_temperature, drink, ambience)
+    }
+
+    fn time_until_frozen(
+        &self,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Option<Duration>, CoolingError> {
+        super::time_until_frozen(initial_temperature, drink, ambience)
+    }
+}
+
+/// Thermal conductivity of ice in W / (m * K)
+const THERMAL_CONDUCTIVITY_ICE: f64 = 2.2;
+/// Time step of the solver in s
+const STEP: f64 = 10.;
+/// Temperature range in K over which the latent heat of a shell is
+/// spread while solving for the temperatures
+const MUSHY_RANGE: f64 = 0.5;
+/// How far the solver looks ahead for an event that may never come, in s
+pub(crate) const HORIZON: f64 = 3. * 24. * 3600.;
+/// Time between two samples of a [`Trajectory`] in s, a multiple of [`STEP`]
+const SAMPLE_INTERVAL: f64 = 60.;
+
+#[derive(Debug, Clone, Copy)]
+/// Implicit finite-difference solver for heat conduction in a
+/// cylinder of liquid, see the module documentation
+///
+/// The container is approximated by a long cylinder of the same
+/// volume whose side has the whole surface area of the container,
+/// so heat leaving through the ends is spread over the side. Its
+/// wall and any layers on it store little heat and only count as a
+/// resistance. Freezing uses the enthalpy method, so shells freeze
+/// from the wall inwards. A calibration scales the resistance
+/// outside the liquid by how much the measured cooling coefficient
+/// differs from the calculated one.
+pub struct FiniteDifference {
+    /// Number of shells the liquid is split into
+    pub shells: usize,
+}
+
+impl FiniteDifference {
+    fn simulation(
+        &self,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Simulation, CoolingError> {
+        // Same checks as the exponential model
+        cooling_coefficient(drink, ambience)?;
+        Ok(Simulation::new(
+            self.shells.max(1),
+            initial_temperature,
+            drink,
+            ambience,
+        ))
+    }
+}
+
+impl CoolingModel for FiniteDifference {
+    fn resolves_gradients(&self) -> bool {
+        true
+    }
+
+    fn temperature_after_time(
+        &self,
+        time: Duration,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Temperature, CoolingError> {
+        let mut simulation = self.simulation(initial_temperature, drink, ambience)?;
+        simulation.run_for(seconds(time));
+        Ok(simulation.mixed_temperature())
+    }
+
+    fn phase_after_time(
+        &self,
+        time: Duration,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Phase, CoolingError> {
+        let mut simulation = self.simulation(initial_temperature, drink, ambience)?;
+        simulation.run_for(seconds(time));
+        Ok(simulation.phase())
+    }
+
+    fn time_until_temperature(
+        &self,
+        target_temperature: Temperature,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Duration, CoolingError> {
+        let mut simulation = self.simulation(initial_temperature, drink, ambience)?;
+        if !target_reachable(
+            target_temperature,
+            initial_temperature,
+            sink_temperature(drink, ambience),
+        ) {
+            return Err(CoolingError::TargetUnreachable);
+        }
+        let target = target_temperature.as_kelvin();
+        let cooling = initial_temperature.as_kelvin() > target;
+        let past_target = |temperature: f64| match cooling {
+            true => temperature <= target,
+            false => temperature >= target,
+        };
+
+        let mut previous = (0., initial_temperature.as_kelvin());
+        while !past_target(previous.1) && simulation.time < HORIZON {
+            simulation.step(STEP);
+            let current = (simulation.time, simulation.mixed_temperature().as_kelvin());
+            if past_target(current.1) {
+                // Linear between the last two steps
+                let fraction = (target - previous.1) / (current.1 - previous.1);
+                return Ok(duration_from_seconds(
+                    previous.0 + fraction * (current.0 - previous.0),
+                ));
+            }
+            previous = current;
+        }
+
+        Err(CoolingError::BeyondHorizon)
+    }
+
+    fn time_until_freezing(
+        &self,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Option<Duration>, CoolingError> {
+        let mut simulation = self.simulation(initial_temperature, drink, ambience)?;
+        if sink_temperature(drink, ambience).as_kelvin() >= drink.freezing_point.as_kelvin() {
+            return Ok(None);
+        }
+
+        Ok(simulation
+            .run_until(|simulation| simulation.phase() != Phase::Liquid)
+            .map(duration_from_seconds))
+    }
+
+    fn time_until_frozen(
+        &self,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Option<Duration>, CoolingError> {
+        let mut simulation = self.simulation(initial_temperature, drink, ambience)?;
+        if sink_temperature(drink, ambience).as_kelvin() >= drink.freezing_point.as_kelvin() {
+            return Ok(None);
+        }
+
+        Ok(simulation
+            .run_until(|simulation| simulation.phase() == Phase::Frozen)
+            .map(duration_from_seconds))
+    }
+
+    /// One run of the solver sampled on the way
+    fn trajectory(
+        &self,
+        span: Duration,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Result<Trajectory, CoolingError> {
+        let mut simulation = self.simulation(initial_temperature, drink, ambience)?;
+        let samples = sample_times(seconds(span))
+            .map(|time| {
+                simulation.run_for(time);
+                (
+                    time,
+                    simulation.mixed_temperature().as_kelvin(),
+                    simulation.phase(),
+                )
+            })
+            .collect();
+        Ok(Trajectory { samples })
+    }
+}
+
+/// State of the shells of a [`FiniteDifference`] run
+struct Simulation {
+    /// Seconds simulated so far
+    time: f64,
+    /// Enthalpy of every shell from the core outwards in J / m^3,
+    /// zero is liquid at the freezing point
+    enthalpy: Vec<f64>,
+    /// Volume of one shell per meter of length in m^2, all shells
+    /// hold the same volume so the thin ones near the wall resolve
+    /// the steep gradient there
+    volume: f64,
+    /// Logarithm of the ratio between the inner radius of every
+    /// shell and its middle, and between its middle and outer radius
+    inner_logs: Vec<f64>,
+    outer_logs: Vec<f64>,
+    /// Buffers reused by every step
+    temperatures: Vec<f64>,
+    conductances: Vec<f64>,
+    sweep: Vec<(f64, f64)>,
+    solved: Vec<f64>,
+    /// Thermal resistance of the outside, the wall and the boundary
+    /// layer of liquid along it per meter of length in m * K / W
+    outer_resistance: f64,
+    /// In K
+    sink_temperature: f64,
+    /// In K
+    freezing_point: f64,
+    /// Volumetric heat capacities in J / (m^3 * K)
+    heat_capacity_liquid: f64,
+    heat_capacity_frozen: f64,
+    /// Latent heat of the water in J / m^3
+    latent_heat: f64,
+    /// Thermal conductivity of the still liquid in W / (m * K)
+    thermal_conductivity: f64,
+    /// Factor of the Rayleigh number per kelvin between wall and core
+    rayleigh_per_kelvin: f64,
+    /// Prandtl number of the liquid
+    prandtl: f64,
+}
+
+impl Simulation {
+    fn new(
+        shells: usize,
+        initial_temperature: Temperature,
+        drink: &Drink,
+        ambience: &Ambience,
+    ) -> Self {
+        let volume = drink.container.volume;
+        // Cylinder with the volume and the whole surface area of the
+        // container on its side, V = pi * r^2 * h and A = 2 * pi * r * h
+        let radius = 2. * volume / drink.container.surface_area;
+        let boundary = |shell: usize| radius * (shell as f64 / shells as f64).sqrt();
+        // Radius that splits the shell into halves of equal volume
+        let middle = |shell: usize| radius * ((shell as f64 + 0.5) / shells as f64).sqrt();
+        let inner_logs = (0..shells)
+            .map(|shell| match shell {
+                0 => 0.,
+                _ => (middle(shell) / boundary(shell)).ln(),
+            })
+            .collect();
+        let outer_logs = (0..shells)
+            .map(|shell| (boundary(shell + 1) / middle(shell)).ln())
+            .collect();
+
+        let mixture = Mixture::new(drink.alcohol_percentage).with_sugar(drink.sugar_content);
+        let heat_capacity_liquid = drink.heat_capacity / volume;
+        let thermal_conductivity = mixture.thermal_conductivity();
+
+        let freezing_point = drink.freezing_point.as_kelvin();
+        let heat_capacity_frozen = drink.heat_capacity_frozen / volume;
+        let latent_heat = drink.latent_heat / volume;
+        // Below its freezing point the drink starts as ice
+        let initial_enthalpy = match initial_temperature.as_kelvin() - freezing_point {
+            difference if difference < 0. => heat_capacity_frozen * difference - latent_heat,
+            difference => heat_capacity_liquid * difference,
+        };
+        // A calibrated drink cools faster or slower than calculated,
+        // all of the difference is put outside the liquid
+        let calibration = drink
+            .calibrated_cooling_coefficient(ambience)
+            .map_or(1., |calibrated| {
+                drink.cooling_coefficient(outer_heat_transfer_coefficient(drink, ambience))
+                    / calibrated
+            });
+
+        Simulation {
+            time: 0.,
+            enthalpy: vec![initial_enthalpy; shells],
+            volume: PI * radius * radius / shells as f64,
+            inner_logs,
+            outer_logs,
+            temperatures: vec![0.; shells],
+            conductances: vec![0.; shells],
+            sweep: vec![(0., 0.); shells],
+            solved: vec![0.; shells],
+            outer_resistance: (1. / outer_heat_transfer_coefficient(drink, ambience)
+                + drink.container.get_thermal_resistance()
+                + 1. / mixture.heat_transfer_coefficient())
+                * calibration
+                / (2. * PI * radius),
+            sink_temperature: sink_temperature(drink, ambience).as_kelvin(),
+            freezing_point,
+            heat_capacity_liquid,
+            heat_capacity_frozen,
+            latent_heat,
+            thermal_conductivity,
+            rayleigh_per_kelvin: mixture.rayleigh_number(radius, 1.),
+            prandtl: mixture.prandtl_number(),
+        }
+    }
+
+    /// Temperature in K of a shell with the given enthalpy
+    fn temperature(&self, enthalpy: f64) -> f64 {
+        if enthalpy >= 0. {
+            self.freezing_point + enthalpy / self.heat_capacity_liquid
+        } else if enthalpy >= -self.latent_heat {
+            self.freezing_point
+        } else {
+            self.freezing_point + (enthalpy + self.latent_heat) / self.heat_capacity_frozen
+        }
+    }
+
+    /// Thermal conductivity of a shell in W / (m * K), liquid shells
+    /// are mixed by natural convection with the given factor
+    fn conductivity(&self, enthalpy: f64, mixing: f64) -> f64 {
+        if enthalpy >= 0. {
+            self.thermal_conductivity * mixing
+        } else if enthalpy >= -self.latent_heat {
+            self.thermal_conductivity
+        } else {
+            THERMAL_CONDUCTIVITY_ICE
+        }
+    }
+
+    /// Thermal resistance per meter of length in m * K / W across
+    /// the part of a shell with the given logarithm of its radii
+    fn resistance(&self, shell: usize, log: f64, mixing: f64) -> f64 {
+        log / (2. * PI * self.conductivity(self.enthalpy[shell], mixing))
+    }
+
+    /// Advances by the given seconds
+    fn step(&mut self, step: f64) {
+        let shells = self.enthalpy.len();
+        for shell in 0..shells {
+            self.temperatures[shell] = self.temperature(self.enthalpy[shell]);
+        }
+
+        // Natural convection between the wall and the core
+        let rayleigh =
+            self.rayleigh_per_kelvin * (self.temperatures[0] - self.temperatures[shells - 1]).abs();
+        let mixing = convection_factor(rayleigh, self.prandtl);
+
+        // Conductance per meter of length in W / (m * K) between
+        // every shell and the next one outwards, the last one to
+        // the ambience
+        for shell in 0..shells {
+            let inside = self.resistance(shell, self.outer_logs[shell], mixing);
+            let outside = match shell + 1 < shells {
+                true => self.resistance(shell + 1, self.inner_logs[shell + 1], mixing),
+                false => self.outer_resistance,
+            };
+            self.conductances[shell] = 1. / (inside + outside);
+        }
+
+        // Backward Euler for the temperatures, with the heat
+        // capacity of every shell at the start of the step, mushy
+        // shells hold their latent heat over a small range
+        let mut previous = (0., 0.);
+        for shell in 0..shells {
+            let capacity = self.volume / step
+                * match self.enthalpy[shell] {
+                    enthalpy if enthalpy >= 0. => self.heat_capacity_liquid,
+                    enthalpy if enthalpy >= -self.latent_heat => self.latent_heat / MUSHY_RANGE,
+                    _ => self.heat_capacity_frozen,
+                };
+            let inner = if shell > 0 {
+                self.conductances[shell - 1]
+            } else {
+                0.
+            };
+            let outer = self.conductances[shell];
+            // The ambience is known, the other neighbours are solved for
+            let (upper, right) = match shell + 1 < shells {
+                true => (outer, capacity * self.temperatures[shell]),
+                false => (
+                    0.,
+                    capacity * self.temperatures[shell] + outer * self.sink_temperature,
+                ),
+            };
+
+            // Forward sweep of the tridiagonal system
+            let diagonal = capacity + inner + outer - inner * previous.0;
+            previous = (upper / diagonal, (right + inner * previous.1) / diagonal);
+            self.sweep[shell] = previous;
+        }
+        let mut next = 0.;
+        for shell in (0..shells).rev() {
+            let (factor, value) = self.sweep[shell];
+            next = value + factor * next;
+            self.solved[shell] = next;
+        }
+
+        // Enthalpy follows from the heat flowing with the new
+        // temperatures, so no energy is lost between the phases
+        let mut inflow = 0.;
+        for shell in 0..shells {
+            let outside = match shell + 1 < shells {
+                true => self.solved[shell + 1],
+                false => self.sink_temperature,
+            };
+            let outflow = self.conductances[shell] * (self.solved[shell] - outside);
+            self.enthalpy[shell] += (inflow - outflow) * step / self.volume;
+            inflow = outflow;
+        }
+        self.time += step;
+    }
+
+    fn run_for(&mut self, seconds: f64) {
+        while self.time < seconds {
+            self.step((seconds - self.time).min(STEP));
+        }
+    }
+
+    /// Runs until the condition holds, `None` if it doesn't within
+    /// the horizon, the time is that of the first step it holds after
+    fn run_until(&mut self, condition: impl Fn(&Simulation) -> bool) -> Option<f64> {
+        while self.time < HORIZON {
+            if condition(self) {
+                return Some(self.time);
+            }
+            self.step(STEP);
+        }
+        None
+    }
+
+    /// Temperature once the drink is poured and mixed
+    fn mixed_temperature(&self) -> Temperature {
+        let enthalpy = self.enthalpy.iter().sum::<f64>() / self.enthalpy.len() as f64;
+        Temperature::new(self.temperature(enthalpy))
+    }
+
+    fn phase(&self) -> Phase {
+        if self.enthalpy.iter().all(|&enthalpy| enthalpy >= 0.) {
+            return Phase::Liquid;
+        }
+        if self
+            .enthalpy
+            .iter()
+            .all(|&enthalpy| enthalpy < -self.latent_heat)
+        {
+            return Phase::Frozen;
+        }
+
+        let frozen = self
+            .enthalpy
+            .iter()
+            .map(|enthalpy| (-enthalpy / self.latent_heat).clamp(0., 1.))
+            .sum::<f64>();
+        Phase::Freezing(frozen / self.enthalpy.len() as f64)
+    }
+}
+
+fn seconds(duration: Duration) -> f64 {
+    duration.num_milliseconds() as f64 / 1000.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        calibration::Calibration,
+        catalog,
+        drink::{Layer, LayerMaterial},
+        fixtures::assert_close,
+    };
+
+    const FD: FiniteDifference = FiniteDifference { shells: 8 };
+
+    fn minutes_to_target(model: &dyn CoolingModel, drink: &Drink, ambience: &Ambience) -> f64 {
+        model
+            .time_until_temperature(
+                catalog::target_beer().temperature,
+                catalog::room_temperature().temperature,
+                drink,
+                ambience,
+            )
+            .unwrap()
+            .num_seconds() as f64
+            / 60.
+    }
+
+    #[test]
+    fn agrees_with_the_exponential_model_for_a_can() {
+        let drink = catalog::beer_330_can();
+        let fridge = catalog::fridge();
+        let exponential = minutes_to_target(&Exponential, &drink, &fridge);
+        let fd = minutes_to_target(&FD, &drink, &fridge);
+        assert_close(fd / exponential, 1., 0.1);
+    }
+
+    #[test]
+    fn follows_a_calibration() {
+        let fridge = catalog::fridge();
+        let drink = catalog::beer_500_glass();
+        let calibrated = drink.calibrated(
+            &fridge,
+            &[Calibration {
+                drink_id: drink.id,
+                ambience_id: fridge.id,
+                cooling_coefficient: 2. * cooling_coefficient(&drink, &fridge).unwrap(),
+            }],
+        );
+
+        let ratio =
+            minutes_to_target(&FD, &drink, &fridge) / minutes_to_target(&FD, &calibrated, &fridge);
+        assert_close(ratio, 2., 0.1);
+    }
+
+    #[test]
+    fn target_beyond_the_horizon_is_an_error() {
+        let drink = (0..4).fold(catalog::lemonade_1000_pet(), |drink, _| {
+            drink.with_layer(Layer::new(LayerMaterial::Neoprene))
+        });
+        let fridge = catalog::fridge();
+        let result = FD.time_until_temperature(
+            Temperature::new(fridge.temperature.as_kelvin() + 0.001),
+            catalog::room_temperature().temperature,
+            &drink,
+            &fridge,
+        );
+        assert!(
+            matches!(result, Err(CoolingError::BeyondHorizon)),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn trajectory_matches_the_simulation() {
+        let drink = catalog::beer_500_glass();
+        let freezer = catalog::freezer();
+        let initial = catalog::room_temperature().temperature;
+        let span = Duration::seconds(3 * 3600 + 25);
+        let trajectory = FD.trajectory(span, initial, &drink, &freezer).unwrap();
+
+        let simulated = |time| {
+            FD.temperature_after_time(time, initial, &drink, &freezer)
+                .unwrap()
+                .as_kelvin()
+        };
+        assert_eq!(trajectory.last_temperature().as_kelvin(), simulated(span));
+        for minutes in [0, 17, 90] {
+            let time = Duration::minutes(minutes);
+            let temperature = trajectory.temperature_after_time(time).unwrap();
+            assert_close(temperature.as_kelvin(), simulated(time), 1e-9);
+        }
+        let between = Duration::seconds(37 * 60 + 30);
+        let temperature = trajectory.temperature_after_time(between).unwrap();
+        assert_close(temperature.as_kelvin(), simulated(between), 0.05);
+        assert!(trajectory
+            .temperature_after_time(span + Duration::seconds(1))
+            .is_none());
+    }
+
+    #[test]
+    fn thaws_like_the_exponential_model() {
+        let drink = catalog::beer_500_glass();
+        let room = catalog::room_temperature();
+        let frozen = catalog::freezer().temperature;
+        let time = |model: &dyn CoolingModel| {
+            model
+                .time_until_temperature(catalog::target_beer().temperature, frozen, &drink, &room)
+                .unwrap()
+                .num_seconds() as f64
+        };
+        let ratio = time(&FD) / time(&Exponential);
+        assert_close(ratio, 1., 0.05);
+    }
+}
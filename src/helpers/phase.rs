@@ -0,0 +1,11 @@
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+/// State of the water in a drink
+pub enum Phase {
+    #[default]
+    Liquid,
+    /// Ice is forming, value between 0. and 1. is the
+    /// fraction of water that is already frozen
+    Freezing(f64),
+    /// All water is frozen, glass bottles are likely to burst
+    Frozen,
+}
@@ -0,0 +1,148 @@
+use chrono::{DateTime, Days, Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    model::{CoolingModel, CoolingModelKind},
+    CoolingError, TimerPreset,
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+/// Drink that should be cold at a given time
+pub struct PlannedDrink {
+    pub id: Uuid,
+    pub preset: TimerPreset,
+    pub ready_at: DateTime<Local>,
+    /// Latest moment to put the drink in
+    pub put_in_at: DateTime<Local>,
+    /// The "put it in now" reminder went off
+    #[serde(default)]
+    pub reminded: bool,
+    /// Model the put in time was predicted with
+    #[serde(default)]
+    pub model: CoolingModelKind,
+}
+
+impl PlannedDrink {
+    pub fn new(
+        preset: TimerPreset,
+        model: CoolingModelKind,
+        ready_at: DateTime<Local>,
+    ) -> Result<Self, CoolingError> {
+        let put_in_at = ready_at
+            - model.time_until_temperature(
+                preset.target_ambience.temperature,
+                preset.initial_ambience.temperature,
+                &preset.drink,
+                &preset.ambient_ambience,
+            )?;
+
+        Ok(PlannedDrink {
+            id: Uuid::new_v4(),
+            preset,
+            ready_at,
+            put_in_at,
+            reminded: false,
+            model,
+        })
+    }
+
+    pub fn is_due(&self, now: DateTime<Local>) -> bool {
+        now >= self.put_in_at
+    }
+}
+
+/// Next time the wall clock shows the given time, today or tomorrow
+pub fn next_occurrence(time: NaiveTime, now: DateTime<Local>) -> DateTime<Local> {
+    let today = now.date_naive().and_time(time);
+    let date_time = match today.and_local_timezone(Local).earliest() {
+        Some(date_time) if date_time > now => Some(date_time),
+        _ => (today + Days::new(1)).and_local_timezone(Local).earliest(),
+    };
+
+    // Only a skipped hour at a daylight saving change has no local time
+    date_time.unwrap_or(now)
+}
+
+/// Plans grouped by the time they should be ready, each group
+/// ordered by when its drinks have to be put in
+pub fn staggered_schedule(plans: &[PlannedDrink]) -> Vec<(DateTime<Local>, Vec<PlannedDrink>)> {
+    let mut plans = plans.to_vec();
+    plans.sort_by_key(|plan| (plan.ready_at, plan.put_in_at));
+
+    let mut schedule: Vec<(DateTime<Local>, Vec<PlannedDrink>)> = vec![];
+    for plan in plans {
+        match schedule.last_mut() {
+            Some((ready_at, group)) if *ready_at == plan.ready_at => group.push(plan),
+            _ => schedule.push((plan.ready_at, vec![plan])),
+        }
+    }
+    schedule
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::helpers::{catalog, fixtures::start};
+
+    fn beer_in_freezer() -> TimerPreset {
+        TimerPreset::new(
+            "Bier",
+            "",
+            catalog::beer_500_glass(),
+            catalog::room_temperature(),
+            catalog::freezer(),
+            catalog::target_beer(),
+        )
+    }
+
+    fn plan(ready_in_hours: i64) -> PlannedDrink {
+        PlannedDrink::new(
+            beer_in_freezer(),
+            CoolingModelKind::Exponential,
+            start() + Duration::hours(ready_in_hours),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn put_in_leaves_the_time_to_cool() {
+        let preset = beer_in_freezer();
+        let plan = plan(3);
+        let needed = CoolingModelKind::Exponential
+            .time_until_temperature(
+                preset.target_ambience.temperature,
+                preset.initial_ambience.temperature,
+                &preset.drink,
+                &preset.ambient_ambience,
+            )
+            .unwrap();
+        assert_eq!(plan.ready_at - plan.put_in_at, needed);
+        assert!(!plan.is_due(plan.put_in_at - Duration::seconds(1)));
+        assert!(plan.is_due(plan.put_in_at));
+    }
+
+    #[test]
+    fn times_already_past_today_are_tomorrow() {
+        let later = NaiveTime::from_hms_opt(20, 0, 0).unwrap();
+        let earlier = NaiveTime::from_hms_opt(8, 0, 0).unwrap();
+        let now = start();
+        assert_eq!(next_occurrence(later, now), now + Duration::hours(2));
+        assert_eq!(next_occurrence(earlier, now), now + Duration::hours(14));
+    }
+
+    #[test]
+    fn schedule_groups_by_ready_time() {
+        let mut can = plan(3);
+        can.put_in_at += Duration::minutes(30);
+        let plans = [plan(5), can.clone(), plan(3)];
+
+        let schedule = staggered_schedule(&plans);
+        assert_eq!(schedule.len(), 2);
+        assert_eq!(schedule[0].0, start() + Duration::hours(3));
+        assert_eq!(schedule[0].1.len(), 2);
+        assert_eq!(schedule[0].1[1].id, can.id);
+    }
+}
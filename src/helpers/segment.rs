@@ -0,0 +1,551 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    cooling_coefficient, duration_from_seconds,
+    model::{self, CoolingModel, Trajectory},
+    sink_temperature, target_reachable,
+    validity::validity,
+    Ambience, CoolingError, Drink, Phase, Temperature,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Period of time a drink spends in one ambience, lasts until
+/// the next segment starts
+pub struct Segment {
+    pub ambience: Ambience,
+    pub timestamp_started: DateTime<Local>,
+    /// The drink was taken out to pause the timer
+    #[serde(default)]
+    pub paused: bool,
+    /// Cooling coefficient in 1 / s fitted to measurements of the
+    /// drink in this ambience, looked up when it was moved here
+    #[serde(default)]
+    pub calibration: Option<f64>,
+}
+
+impl Segment {
+    /// The drink with the calibration of this segment, if any
+    pub fn drink<'a>(&self, drink: &'a Drink) -> Cow<'a, Drink> {
+        match self.calibration {
+            Some(cooling_coefficient) => Cow::Owned(Drink {
+                calibration: Some((self.ambience.id, cooling_coefficient)),
+                ..drink.clone()
+            }),
+            None => Cow::Borrowed(drink),
+        }
+    }
+}
+
+/// Temperature of the drink at the start of every segment,
+/// chaining [`CoolingModel::temperature_after_time`] from one to the next
+///
+/// Only the temperature is carried over, a partially frozen
+/// drink starts the next segment as liquid at its freezing point.
+fn start_temperatures(
+    segments: &[Segment],
+    initial_temperature: Temperature,
+    drink: &Drink,
+    model: &dyn CoolingModel,
+) -> Result<Vec<Temperature>, CoolingError> {
+    let mut temperatures = vec![initial_temperature];
+    for window in segments.windows(2) {
+        let previous = temperatures[temperatures.len() - 1];
+        temperatures.push(model.temperature_after_time(
+            window[1].timestamp_started - window[0].timestamp_started,
+            previous,
+            &window[0].drink(drink),
+            &window[0].ambience,
+        )?);
+    }
+    Ok(temperatures)
+}
+
+/// Temperature of the drink when it entered its current ambience
+pub fn current_start_temperature(
+    segments: &[Segment],
+    initial_temperature: Temperature,
+    drink: &Drink,
+    model: &dyn CoolingModel,
+) -> Result<Temperature, CoolingError> {
+    Ok(
+        start_temperatures(segments, initial_temperature, drink, model)?
+            .last()
+            .copied()
+            .unwrap_or(initial_temperature),
+    )
+}
+
+/// Whether the cooling of the drink can be predicted in every segment
+pub fn check(segments: &[Segment], drink: &Drink) -> Result<(), CoolingError> {
+    segments.iter().try_for_each(|segment| {
+        cooling_coefficient(&segment.drink(drink), &segment.ambience).map(|_| ())
+    })
+}
+
+/// Largest relative uncertainty of the predictions in any segment,
+/// see [`crate::helpers::validity::Validity::uncertainty`]
+pub fn uncertainty(
+    segments: &[Segment],
+    initial_temperature: Temperature,
+    drink: &Drink,
+    model: &dyn CoolingModel,
+) -> Result<f64, CoolingError> {
+    let start_temperatures = start_temperatures(segments, initial_temperature, drink, model)?;
+    segments.iter().zip(start_temperatures).try_fold(
+        0.,
+        |uncertainty: f64, (segment, start_temperature)| {
+            let validity = validity(start_temperature, &segment.drink(drink), &segment.ambience)?;
+            Ok(uncertainty.max(validity.uncertainty(model.resolves_gradients())))
+        },
+    )
+}
+
+/// Index of the segment the drink is in at the given time
+fn segment_index_at(segments: &[Segment], time: DateTime<Local>) -> usize {
+    segments
+        .iter()
+        .rposition(|segment| segment.timestamp_started <= time)
+        .unwrap_or_default()
+}
+
+/// Temperature of the drink at the given time
+pub fn temperature_at(
+    time: DateTime<Local>,
+    segments: &[Segment],
+    initial_temperature: Temperature,
+    drink: &Drink,
+    model: &dyn CoolingModel,
+) -> Result<Temperature, CoolingError> {
+    let index = segment_index_at(segments, time);
+    let segment = &segments[index];
+
+    model.temperature_after_time(
+        time - segment.timestamp_started,
+        start_temperatures(segments, initial_temperature, drink, model)?[index],
+        &segment.drink(drink),
+        &segment.ambience,
+    )
+}
+
+/// State of the water in the drink at the given time
+pub fn phase_at(
+    time: DateTime<Local>,
+    segments: &[Segment],
+    initial_temperature: Temperature,
+    drink: &Drink,
+    model: &dyn CoolingModel,
+) -> Result<Phase, CoolingError> {
+    let index = segment_index_at(segments, time);
+    let segment = &segments[index];
+
+    model.phase_after_time(
+        time - segment.timestamp_started,
+        start_temperatures(segments, initial_temperature, drink, model)?[index],
+        &segment.drink(drink),
+        &segment.ambience,
+    )
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Predicted course of the drink through all segments, made once
+/// when the segments change and looked up from then on, so models
+/// that simulate don't start over for every point in time
+pub struct Course {
+    /// Start of every segment, the temperature of the drink then and
+    /// its trajectory until the next segment starts, the last one
+    /// until the horizon of the models
+    parts: Vec<(DateTime<Local>, Temperature, Trajectory)>,
+}
+
+impl Course {
+    pub fn new(
+        segments: &[Segment],
+        initial_temperature: Temperature,
+        drink: &Drink,
+        model: &dyn CoolingModel,
+    ) -> Result<Self, CoolingError> {
+        let mut parts: Vec<(DateTime<Local>, Temperature, Trajectory)> = vec![];
+        for (index, segment) in segments.iter().enumerate() {
+            let start_temperature = parts
+                .last()
+                .map_or(initial_temperature, |(.., trajectory)| {
+                    trajectory.last_temperature()
+                });
+            let span = match segments.get(index + 1) {
+                Some(next) => next.timestamp_started - segment.timestamp_started,
+                None => duration_from_seconds(model::HORIZON),
+            };
+            let trajectory = model.trajectory(
+                span,
+                start_temperature,
+                &segment.drink(drink),
+                &segment.ambience,
+            )?;
+            parts.push((segment.timestamp_started, start_temperature, trajectory));
+        }
+        Ok(Course { parts })
+    }
+
+    /// Temperature of the drink when it entered its current ambience
+    pub fn current_start_temperature(&self) -> Option<Temperature> {
+        self.parts
+            .last()
+            .map(|(_, start_temperature, _)| *start_temperature)
+    }
+
+    /// Temperature of the drink at the given time, `None` past the
+    /// predicted time
+    pub fn temperature_at(&self, time: DateTime<Local>) -> Option<Temperature> {
+        let (started, _, trajectory) = self.part_at(time)?;
+        trajectory.temperature_after_time(time - *started)
+    }
+
+    /// State of the water in the drink at the given time, `None`
+    /// past the predicted time
+    pub fn phase_at(&self, time: DateTime<Local>) -> Option<Phase> {
+        let (started, _, trajectory) = self.part_at(time)?;
+        trajectory.phase_after_time(time - *started)
+    }
+
+    fn part_at(
+        &self,
+        time: DateTime<Local>,
+    ) -> Option<&(DateTime<Local>, Temperature, Trajectory)> {
+        let index = self
+            .parts
+            .iter()
+            .rposition(|(started, ..)| *started <= time)
+            .unwrap_or_default();
+        self.parts.get(index)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// When the drink got past the target temperature, and when it
+/// crosses back out of the target range in its current ambience
+pub struct TargetReached {
+    pub reached: DateTime<Local>,
+    /// E.g. a finished drink paused at room temperature, `None` if
+    /// it stays in range
+    pub left: Option<DateTime<Local>>,
+}
+
+impl TargetReached {
+    /// When the drink reached the target, `None` once it left the
+    /// target range again by the given time
+    pub fn at(&self, time: DateTime<Local>) -> Option<DateTime<Local>> {
+        self.left
+            .is_none_or(|left| time < left)
+            .then_some(self.reached)
+    }
+}
+
+/// When the drink reached, or will reach, the target temperature
+///
+/// Returns `None` if the drink is not past the target and won't
+/// get there in its current ambience, e.g. while paused at
+/// room temperature.
+pub fn time_target_reached(
+    segments: &[Segment],
+    initial_temperature: Temperature,
+    target_temperature: Temperature,
+    drink: &Drink,
+    model: &dyn CoolingModel,
+) -> Result<Option<TargetReached>, CoolingError> {
+    let cooling = initial_temperature.as_kelvin() > target_temperature.as_kelvin();
+    let past_target = |temperature: Temperature| match cooling {
+        true => temperature.as_kelvin() <= target_temperature.as_kelvin(),
+        false => temperature.as_kelvin() >= target_temperature.as_kelvin(),
+    };
+
+    let mut reached = None;
+    let start_temperatures = start_temperatures(segments, initial_temperature, drink, model)?;
+    for (index, segment) in segments.iter().enumerate() {
+        let start_temperature = start_temperatures[index];
+        if !target_reachable(
+            target_temperature,
+            start_temperature,
+            sink_temperature(&segment.drink(drink), &segment.ambience),
+        ) {
+            continue;
+        }
+
+        let crossing = segment.timestamp_started
+            + match model.time_until_temperature(
+                target_temperature,
+                start_temperature,
+                &segment.drink(drink),
+                &segment.ambience,
+            ) {
+                Ok(duration) => duration,
+                // Too slow to ever matter while in this ambience
+                Err(CoolingError::BeyondHorizon) => continue,
+                Err(error) => return Err(error),
+            };
+        let next = segments.get(index + 1);
+        if next.is_some_and(|next| next.timestamp_started <= crossing) {
+            continue;
+        }
+
+        reached = match past_target(start_temperature) {
+            // Crossing back out of the target range resets the timer,
+            // in the current ambience only once the drink gets there
+            true => match next {
+                Some(_) => None,
+                None => reached.map(|reached| TargetReached {
+                    left: Some(crossing),
+                    ..reached
+                }),
+            },
+            false => Some(TargetReached {
+                reached: crossing,
+                left: None,
+            }),
+        };
+    }
+
+    Ok(reached)
+}
+
+/// When ice starts to form in the current ambience, see [`CoolingModel::time_until_freezing`]
+pub fn time_freezing(
+    segments: &[Segment],
+    initial_temperature: Temperature,
+    drink: &Drink,
+    model: &dyn CoolingModel,
+) -> Result<Option<DateTime<Local>>, CoolingError> {
+    let Some(segment) = segments.last() else {
+        return Ok(None);
+    };
+    let start_temperature = current_start_temperature(segments, initial_temperature, drink, model)?;
+
+    Ok(model
+        .time_until_freezing(start_temperature, &segment.drink(drink), &segment.ambience)?
+        .map(|duration| segment.timestamp_started + duration))
+}
+
+/// When the drink is frozen solid in the current ambience, see [`CoolingModel::time_until_frozen`]
+pub fn time_frozen(
+    segments: &[Segment],
+    initial_temperature: Temperature,
+    drink: &Drink,
+    model: &dyn CoolingModel,
+) -> Result<Option<DateTime<Local>>, CoolingError> {
+    let Some(segment) = segments.last() else {
+        return Ok(None);
+    };
+    let start_temperature = current_start_temperature(segments, initial_temperature, drink, model)?;
+
+    Ok(model
+        .time_until_frozen(start_temperature, &segment.drink(drink), &segment.ambience)?
+        .map(|duration| segment.timestamp_started + duration))
+}
+
+/// Reminders to move the bottle that are due by the given time,
+/// counted in every segment from its start
+///
+/// Ambiences with nothing to do by hand don't remind, see
+/// [`crate::helpers::ambience::Agitation::reminder_interval`], the
+/// one of the preset reminds every `preset_interval` minutes the
+/// user picked instead.
+pub fn rotation_reminders_due(
+    segments: &[Segment],
+    now: DateTime<Local>,
+    preset_ambience: &Ambience,
+    preset_interval: Option<u32>,
+) -> u32 {
+    segments
+        .iter()
+        .enumerate()
+        .map(|(index, segment)| {
+            let ambience = &segment.ambience;
+            let interval = ambience.agitation.reminder_interval().and(
+                match ambience.id == preset_ambience.id {
+                    true => preset_interval,
+                    false => ambience.agitation.reminder_interval(),
+                },
+            );
+            let Some(minutes) = interval.filter(|minutes| *minutes > 0) else {
+                return 0;
+            };
+            let end = segments
+                .get(index + 1)
+                .map_or(now, |next| next.timestamp_started.min(now));
+            ((end - segment.timestamp_started).num_minutes().max(0) / minutes as i64) as u32
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::helpers::{
+        ambience::Agitation,
+        catalog,
+        fixtures::{assert_close, start},
+        model::Exponential,
+    };
+
+    fn segment(ambience: Ambience, minutes: i64, paused: bool) -> Segment {
+        Segment {
+            ambience,
+            timestamp_started: start() + Duration::minutes(minutes),
+            paused,
+            calibration: None,
+        }
+    }
+
+    /// Target reached by a 500 ml beer from room temperature in the
+    /// given segments
+    fn reached(segments: &[Segment]) -> Option<TargetReached> {
+        time_target_reached(
+            segments,
+            catalog::room_temperature().temperature,
+            catalog::target_beer().temperature,
+            &catalog::beer_500_glass(),
+            &Exponential,
+        )
+        .unwrap()
+    }
+
+    fn finished_in_freezer() -> DateTime<Local> {
+        reached(&[segment(catalog::freezer(), 0, false)])
+            .unwrap()
+            .reached
+    }
+
+    #[test]
+    fn course_follows_the_drink_through_its_segments() {
+        let segments = [
+            segment(catalog::freezer(), 0, false),
+            segment(catalog::room_temperature(), 40, true),
+        ];
+        let initial = catalog::room_temperature().temperature;
+        let drink = catalog::beer_500_glass();
+        let course = Course::new(&segments, initial, &drink, &Exponential).unwrap();
+
+        assert_eq!(
+            course.current_start_temperature(),
+            current_start_temperature(&segments, initial, &drink, &Exponential).ok()
+        );
+        for minutes in [0, 25, 40, 61] {
+            let time = start() + Duration::minutes(minutes);
+            let expected = temperature_at(time, &segments, initial, &drink, &Exponential).unwrap();
+            let temperature = course.temperature_at(time).unwrap();
+            assert_close(temperature.as_kelvin(), expected.as_kelvin(), 1e-9);
+        }
+        let late = start() + Duration::days(4);
+        assert!(course.temperature_at(late).is_none());
+    }
+
+    #[test]
+    fn calibration_applies_in_its_segment_only() {
+        let initial = catalog::room_temperature().temperature;
+        let drink = catalog::beer_500_glass();
+        let uncalibrated = [
+            segment(catalog::fridge(), 0, false),
+            segment(catalog::freezer(), 30, false),
+        ];
+        let mut calibrated = uncalibrated.clone();
+        calibrated[1].calibration = Some(1e-4);
+        let temperature = |segments: &[Segment], minutes| {
+            let time = start() + Duration::minutes(minutes);
+            temperature_at(time, segments, initial, &drink, &Exponential)
+                .unwrap()
+                .as_kelvin()
+        };
+
+        assert_eq!(temperature(&calibrated, 30), temperature(&uncalibrated, 30));
+        let expected = sink_temperature(&drink, &catalog::freezer()).as_kelvin()
+            + (temperature(&uncalibrated, 30)
+                - sink_temperature(&drink, &catalog::freezer()).as_kelvin())
+                * (-1e-4 * 600_f64).exp();
+        assert_close(temperature(&calibrated, 40), expected, 1e-6);
+    }
+
+    #[test]
+    fn rotation_reminders_stop_in_the_fridge() {
+        let bath = catalog::ice_bath().with_agitation(Agitation::Rotated);
+        let segments = [
+            segment(bath.clone(), 0, false),
+            segment(catalog::fridge(), 10, false),
+        ];
+        let due = |minutes| {
+            rotation_reminders_due(
+                &segments,
+                start() + Duration::minutes(minutes),
+                &bath,
+                Some(2),
+            )
+        };
+        assert_eq!(due(9), 4);
+        assert_eq!(due(10), 5);
+        assert_eq!(due(60), 5);
+    }
+
+    #[test]
+    fn rotation_reminders_count_from_the_start_of_each_segment() {
+        let bath = catalog::ice_bath().with_agitation(Agitation::Rotated);
+        let stirred = catalog::ice_bath().with_agitation(Agitation::Stirred);
+        let segments = [
+            segment(catalog::fridge(), 0, false),
+            segment(bath.clone(), 7, false),
+            segment(stirred, 12, false),
+        ];
+        let now = start() + Duration::minutes(23);
+        // One in 5 minutes of the bath, two in 11 minutes of stirring
+        assert_eq!(rotation_reminders_due(&segments, now, &bath, Some(3)), 3);
+        assert_eq!(rotation_reminders_due(&segments, now, &bath, None), 2);
+    }
+
+    #[test]
+    fn pause_after_finishing_keeps_the_finish_until_warm() {
+        let finished = finished_in_freezer();
+        let paused_at = (finished - start()).num_minutes() + 5;
+        let segments = [
+            segment(catalog::freezer(), 0, false),
+            segment(catalog::room_temperature(), paused_at, true),
+        ];
+
+        let reached = reached(&segments).unwrap();
+        assert_eq!(reached.reached, finished);
+        let left = reached.left.unwrap();
+        assert!(left > segments[1].timestamp_started);
+        assert_eq!(reached.at(segments[1].timestamp_started), Some(finished));
+        assert_eq!(reached.at(left + Duration::seconds(1)), None);
+    }
+
+    #[test]
+    fn resume_after_a_short_pause_keeps_the_finish() {
+        let finished = finished_in_freezer();
+        let paused_at = (finished - start()).num_minutes() + 5;
+        let segments = [
+            segment(catalog::freezer(), 0, false),
+            segment(catalog::room_temperature(), paused_at, true),
+            segment(catalog::freezer(), paused_at + 5, false),
+        ];
+
+        let reached = reached(&segments).unwrap();
+        assert_eq!(reached.reached, finished);
+        assert_eq!(reached.left, None);
+    }
+
+    #[test]
+    fn move_into_a_colder_bath_finishes_sooner() {
+        let finished = finished_in_freezer();
+        let segments = [
+            segment(catalog::freezer(), 0, false),
+            segment(catalog::salted_ice_bath(), 10, false),
+        ];
+
+        let reached = reached(&segments).unwrap();
+        assert!(reached.reached > segments[1].timestamp_started);
+        assert!(reached.reached < finished);
+        assert_eq!(reached.left, None);
+    }
+}
@@ -0,0 +1,201 @@
+//! Serving temperatures of drinks
+//!
+//! Each drink type has a range it tastes best in, narrowed down by
+//! its style where one is known. The ideal temperature is what a
+//! timer cools the drink to unless the user picks another target.
+//!
+//! Values follow common recommendations of brewers and sommeliers.
+
+use serde::{Deserialize, Serialize};
+
+use super::{drink::DrinkType, Ambience, Drink, Temperature, TemperatureUnit};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Sub-style of a [`DrinkType`] with its own serving temperature
+pub enum DrinkStyle {
+    Pils,
+    Helles,
+    Weizen,
+    Ipa,
+    Stout,
+    Sekt,
+    WhiteWine,
+    Riesling,
+    Rose,
+    RedWine,
+    DessertWine,
+}
+
+impl DrinkStyle {
+    pub const ALL: [DrinkStyle; 11] = [
+        Self::Pils,
+        Self::Helles,
+        Self::Weizen,
+        Self::Ipa,
+        Self::Stout,
+        Self::Sekt,
+        Self::WhiteWine,
+        Self::Riesling,
+        Self::Rose,
+        Self::RedWine,
+        Self::DessertWine,
+    ];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Pils => "Pils",
+            Self::Helles => "Helles",
+            Self::Weizen => "Weizen",
+            Self::Ipa => "IPA",
+            Self::Stout => "Stout",
+            Self::Sekt => "Sekt",
+            Self::WhiteWine => "Weißwein",
+            Self::Riesling => "Riesling",
+            Self::Rose => "Rosé",
+            Self::RedWine => "Rotwein",
+            Self::DessertWine => "Dessertwein",
+        }
+    }
+
+    pub fn drink_type(&self) -> DrinkType {
+        match self {
+            Self::Pils | Self::Helles | Self::Weizen | Self::Ipa | Self::Stout => DrinkType::Beer,
+            Self::Sekt
+            | Self::WhiteWine
+            | Self::Riesling
+            | Self::Rose
+            | Self::RedWine
+            | Self::DessertWine => DrinkType::Wine,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Where a temperature lies relative to a [`ServingRange`]
+pub enum ServingBand {
+    TooCold,
+    Ideal,
+    TooWarm,
+}
+
+impl ServingBand {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::TooCold => "zu kalt",
+            Self::Ideal => "ideal",
+            Self::TooWarm => "zu warm",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+/// Temperatures a drink is best served at
+pub struct ServingRange {
+    pub coldest: Temperature,
+    pub ideal: Temperature,
+    pub warmest: Temperature,
+}
+
+impl ServingRange {
+    fn celsius(coldest: f64, ideal: f64, warmest: f64) -> Self {
+        let celsius = |value| Temperature::new_with_unit(value, TemperatureUnit::DegCelsius);
+        ServingRange {
+            coldest: celsius(coldest),
+            ideal: celsius(ideal),
+            warmest: celsius(warmest),
+        }
+    }
+
+    pub fn band(&self, temperature: Temperature) -> ServingBand {
+        if temperature.as_kelvin() < self.coldest.as_kelvin() {
+            ServingBand::TooCold
+        } else if temperature.as_kelvin() > self.warmest.as_kelvin() {
+            ServingBand::TooWarm
+        } else {
+            ServingBand::Ideal
+        }
+    }
+
+    /// E.g. "5 – 8 °C"
+    pub fn format(&self, unit: TemperatureUnit) -> String {
+        format!(
+            "{} – {}",
+            self.coldest.format(unit, false),
+            self.warmest.format(unit, true)
+        )
+    }
+}
+
+/// Serving temperatures of the style, or of the whole type if the
+/// style is unknown or belongs to another type
+pub fn serving_range(drink_type: DrinkType, style: Option<DrinkStyle>) -> ServingRange {
+    let style = style.filter(|style| style.drink_type() == drink_type);
+
+    match (drink_type, style) {
+        (_, Some(DrinkStyle::Pils)) => ServingRange::celsius(5., 6., 8.),
+        (_, Some(DrinkStyle::Helles)) => ServingRange::celsius(5., 7., 8.),
+        (_, Some(DrinkStyle::Weizen)) => ServingRange::celsius(6., 7., 9.),
+        (_, Some(DrinkStyle::Ipa)) => ServingRange::celsius(7., 9., 11.),
+        (_, Some(DrinkStyle::Stout)) => ServingRange::celsius(9., 11., 13.),
+        (_, Some(DrinkStyle::Sekt)) => ServingRange::celsius(5., 7., 8.),
+        (_, Some(DrinkStyle::WhiteWine)) => ServingRange::celsius(8., 10., 12.),
+        (_, Some(DrinkStyle::Riesling)) => ServingRange::celsius(7., 9., 11.),
+        (_, Some(DrinkStyle::Rose)) => ServingRange::celsius(8., 10., 12.),
+        (_, Some(DrinkStyle::RedWine)) => ServingRange::celsius(14., 16., 18.),
+        (_, Some(DrinkStyle::DessertWine)) => ServingRange::celsius(8., 10., 12.),
+        (DrinkType::Beer, None) => ServingRange::celsius(5., 6., 9.),
+        (DrinkType::Wine, None) => ServingRange::celsius(8., 12., 18.),
+        (DrinkType::Lemonade, None) => ServingRange::celsius(2., 4., 7.),
+        (DrinkType::Schnaps, None) => ServingRange::celsius(-6., 2., 8.),
+        (DrinkType::Other, None) => ServingRange::celsius(4., 7., 10.),
+    }
+}
+
+impl Drink {
+    pub fn serving_range(&self) -> ServingRange {
+        serving_range(self.drink_type, self.style)
+    }
+
+    /// Target ambience at the ideal serving temperature of the drink
+    pub fn serving_target(&self) -> Ambience {
+        let name = match self.style {
+            Some(style) => style.get_name(),
+            None => self.drink_type.get_name(),
+        };
+        Ambience::new(
+            &format!("Optimal für {name}"),
+            &self.path_to_image,
+            self.serving_range().ideal,
+            None,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{catalog, fixtures::celsius};
+
+    #[test]
+    fn style_narrows_the_range_of_its_type() {
+        let pils = serving_range(DrinkType::Beer, Some(DrinkStyle::Pils));
+        assert_eq!(pils.ideal.as_deg_celsius(), 6.);
+        assert_eq!(pils.warmest.as_deg_celsius(), 8.);
+    }
+
+    #[test]
+    fn style_of_another_type_is_ignored() {
+        let range = serving_range(DrinkType::Lemonade, Some(DrinkStyle::RedWine));
+        assert_eq!(range.ideal.as_deg_celsius(), 4.);
+    }
+
+    #[test]
+    fn bands_include_their_bounds() {
+        let range = catalog::red_wine_750_glass().serving_range();
+        assert_eq!(range.band(celsius(13.9)), ServingBand::TooCold);
+        assert_eq!(range.band(celsius(14.)), ServingBand::Ideal);
+        assert_eq!(range.band(celsius(18.)), ServingBand::Ideal);
+        assert_eq!(range.band(celsius(18.1)), ServingBand::TooWarm);
+        assert_eq!(range.format(TemperatureUnit::DegCelsius), "14 – 18 °C");
+    }
+}
@@ -0,0 +1,61 @@
+use leptos::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Key under which the currently running timers are stored
+pub const RUNNING_TIMERS_KEY: &str = "bier_timer.running_timers";
+
+/// Key under which the drinks created by the user are stored
+pub const CUSTOM_DRINKS_KEY: &str = "bier_timer.custom_drinks";
+
+/// Key under which the timer presets created by the user are stored
+pub const CUSTOM_TIMER_PRESETS_KEY: &str = "bier_timer.custom_timer_presets";
+
+/// Key under which the cooling coefficients fitted to the
+/// user's measurements are stored
+pub const CALIBRATIONS_KEY: &str = "bier_timer.calibrations";
+
+/// Key under which the drinks planned to be cold at a given time are stored
+pub const PLANNED_DRINKS_KEY: &str = "bier_timer.planned_drinks";
+
+/// Key under which the preferred temperature unit is stored
+pub const UNIT_PREFERENCE_KEY: &str = "bier_timer.unit_preference";
+
+/// Key under which the preferred cooling model is stored
+pub const MODEL_PREFERENCE_KEY: &str = "bier_timer.model_preference";
+
+fn local_storage() -> Option<web_sys::Storage> {
+    window().local_storage().ok().flatten()
+}
+
+/// Reads and deserializes a value from the browser's local storage
+///
+/// Returns `None` if there is no value or it can't be read, e.g.
+/// because it was written by an older version of the app.
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let raw = local_storage()?.get_item(key).ok()??;
+
+    match serde_json::from_str(&raw) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            logging::warn!("could not read {key} from local storage: {err}");
+            None
+        }
+    }
+}
+
+/// Serializes a value and writes it to the browser's local storage
+pub fn save<T: Serialize>(key: &str, value: &T) {
+    let Some(storage) = local_storage() else {
+        logging::warn!("local storage is not available");
+        return;
+    };
+
+    match serde_json::to_string(value) {
+        Ok(raw) => {
+            if storage.set_item(key, &raw).is_err() {
+                logging::warn!("could not write {key} to local storage");
+            }
+        }
+        Err(err) => logging::warn!("could not serialize {key}: {err}"),
+    }
+}
@@ -1,12 +1,20 @@
 use chrono::{DateTime, Duration, Local};
 use leptos::*;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::{
+    alarm::AlarmState,
     ambience::{self, Ambience},
-    temperature_after_time, time_until_temperature,
+    batch::BatchPrediction,
+    calibration::{Calibration, Measurement},
+    is_warming,
+    model::CoolingModelKind,
+    segment::{self, Course, Segment, TargetReached},
+    sink_temperature,
     timer_preset::TimerPreset,
-    Drink, Temperature,
+    validity::ConfidenceBand,
+    CoolingError, Drink, Phase, Temperature,
 };
 
 #[derive(Clone)]
@@ -15,49 +23,433 @@ pub struct TimerInfo {
     // timed out?
     pub id: Uuid,
     pub timestamp_started: DateTime<Local>,
-    pub timestamp_finished: DateTime<Local>,
+    /// When the target temperature is reached, `None` if it isn't
+    /// reachable in the current ambience, e.g. while paused
+    pub timestamp_finished: Signal<Option<DateTime<Local>>>,
+    /// When the target temperature is reached at the earliest and
+    /// the latest, given how far the prediction can be trusted
+    pub finished_band: Signal<Option<ConfidenceBand<DateTime<Local>>>>,
+    /// Freeze deadline: when ice starts to form, `None` if the
+    /// ambience is too warm to ever freeze the drink
+    pub timestamp_freezing: Signal<Option<DateTime<Local>>>,
+    /// When the drink is frozen solid, `None` if the drink never freezes
+    pub timestamp_frozen: Signal<Option<DateTime<Local>>>,
     pub drink: Drink,
     pub initial_ambience: Ambience,
+    /// Ambience the timer was started in
     pub ambient_ambience: Ambience,
     pub target_ambience: Ambience,
-    pub current_time_left: Signal<Duration>,
+    /// Ambiences the drink has been in, the last one is the current one
+    pub segments: RwSignal<Vec<Segment>>,
+    /// Predicted course through the segments, `None` if the cooling
+    /// can't be predicted in one of them
+    pub course: Signal<Option<Course>>,
+    pub current_ambience: Signal<Ambience>,
+    pub paused: Signal<bool>,
+    pub current_time_left: Signal<Option<Duration>>,
     pub current_temperature: Signal<Temperature>,
     pub timer_finished: Signal<bool>,
+    pub current_phase: Signal<Phase>,
+    pub freezing_started: Signal<bool>,
+    pub fully_frozen: Signal<bool>,
+    /// Time left until the freeze deadline, `None` if there is none
+    pub current_time_until_freezing: Signal<Option<Duration>>,
+    pub current_state: Signal<TimerState>,
+    pub alarm: RwSignal<AlarmState>,
+    /// Temperatures measured by the user for calibration
+    pub measurements: RwSignal<Vec<Measurement>>,
+    /// Prediction for the current ambience if it is shared with other
+    /// drinks, replaces the single drink model while it applies
+    pub batch: RwSignal<Option<BatchPrediction>>,
+    /// Why the cooling can't be predicted in one of the ambiences,
+    /// the other signals fall back to a drink that doesn't change
+    pub error: Signal<Option<CoolingError>>,
+    /// Minutes between reminders to turn the bottle in the ambience
+    /// the timer was started in, `None` for no reminders there
+    pub rotation_reminder: Option<u32>,
+    /// Reminders to turn the bottle sent so far
+    pub rotation_reminders_sent: RwSignal<u32>,
+    /// Model predicting the temperature of the drink
+    pub model: CoolingModelKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Escalating states of a timer, from counting down to a
+/// forgotten bottle that froze in the freezer
+pub enum TimerState {
+    Running,
+    /// Target temperature reached and no freeze deadline
+    Done,
+    /// Target temperature reached and the freeze deadline is coming
+    /// closer, value between 0. and 1. is the fraction of time
+    /// between finishing and the freeze deadline that has passed
+    Overdue(f64),
+    Freezing,
+    Frozen,
+}
+
+impl TimerState {
+    /// Overdue timers past this fraction are shown as critical
+    pub const CRITICAL_THRESHOLD: f64 = 0.5;
+
+    pub fn is_critical(&self) -> bool {
+        match self {
+            TimerState::Overdue(progress) => *progress >= Self::CRITICAL_THRESHOLD,
+            TimerState::Freezing | TimerState::Frozen => true,
+            _ => false,
+        }
+    }
+}
+
+/// Everything needed to rebuild a running [`TimerInfo`], the
+/// derived signals are recalculated from it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimerSnapshot {
+    pub id: Uuid,
+    pub timestamp_started: DateTime<Local>,
+    pub drink: Drink,
+    pub initial_ambience: Ambience,
+    pub ambient_ambience: Ambience,
+    pub target_ambience: Ambience,
+    #[serde(default)]
+    pub alarm: AlarmState,
+    #[serde(default)]
+    pub measurements: Vec<Measurement>,
+    /// Empty for timers saved before they could be moved
+    #[serde(default)]
+    pub segments: Vec<Segment>,
+    #[serde(default)]
+    pub rotation_reminder: Option<u32>,
+    #[serde(default)]
+    pub rotation_reminders_sent: u32,
+    #[serde(default)]
+    pub model: CoolingModelKind,
 }
 
 impl TimerInfo {
-    pub fn new(preset: TimerPreset, current_time_signal: RwSignal<DateTime<Local>>) -> Self {
-        let start = Local::now();
-        let finished = start
-            + time_until_temperature(
-                preset.target_ambience.temperature,
-                preset.initial_ambience.temperature,
-                &preset.drink,
-                &preset.ambient_ambience,
-            );
-        let current_time_left = Signal::derive(move || finished - current_time_signal.get());
-        let timer_finished = Signal::derive(move || current_time_left.get() < Duration::zero());
+    pub fn new(
+        preset: TimerPreset,
+        model: CoolingModelKind,
+        current_time_signal: RwSignal<DateTime<Local>>,
+    ) -> Self {
+        let timestamp_started = Local::now();
+        TimerInfo::from_snapshot(
+            TimerSnapshot {
+                id: Uuid::new_v4(),
+                timestamp_started,
+                drink: preset.drink,
+                initial_ambience: preset.initial_ambience,
+                segments: vec![Segment {
+                    ambience: preset.ambient_ambience.clone(),
+                    timestamp_started,
+                    paused: false,
+                    calibration: None,
+                }],
+                ambient_ambience: preset.ambient_ambience,
+                target_ambience: preset.target_ambience,
+                alarm: AlarmState::Pending,
+                measurements: vec![],
+                rotation_reminder: preset.rotation_reminder,
+                rotation_reminders_sent: 0,
+                model,
+            },
+            current_time_signal,
+        )
+    }
+
+    /// Rebuilds a timer, e.g. after a page reload, against the
+    /// current time signal
+    pub fn from_snapshot(
+        snapshot: TimerSnapshot,
+        current_time_signal: RwSignal<DateTime<Local>>,
+    ) -> Self {
+        let start = snapshot.timestamp_started;
+        let mut segments = snapshot.segments.clone();
+        if segments.is_empty() {
+            segments.push(Segment {
+                ambience: snapshot.ambient_ambience.clone(),
+                timestamp_started: start,
+                paused: false,
+                calibration: None,
+            });
+        }
+        let segments = create_rw_signal(segments);
+
+        let initial = snapshot.initial_ambience.temperature;
+        let target = snapshot.target_ambience.temperature;
+        let drink = store_value(snapshot.drink.clone());
+        let model = snapshot.model;
+
+        let batch = create_rw_signal::<Option<BatchPrediction>>(None);
+        // Batch prediction made for the current segment, if any
+        let current_batch = move || {
+            let entered = segments.with(|segments| segments.last().map(|s| s.timestamp_started));
+            batch
+                .get()
+                .filter(|prediction| Some(prediction.entered) == entered)
+        };
+
+        let error = Signal::derive(move || {
+            segments
+                .with(|segments| drink.with_value(|drink| segment::check(segments, drink).err()))
+        });
+
+        // Predictions that don't change every second are memoized,
+        // the finite-difference model has to simulate for them
+        let target_reached = Signal::from(create_memo(move |_| match current_batch() {
+            Some(prediction) => prediction.finished.map(|reached| TargetReached {
+                reached,
+                left: None,
+            }),
+            None => segments.with(|segments| {
+                drink.with_value(|drink| {
+                    segment::time_target_reached(segments, initial, target, drink, &model)
+                        .ok()
+                        .flatten()
+                })
+            }),
+        }));
+        let finished = Signal::from(create_memo(move |_| {
+            target_reached.get()?.at(current_time_signal.get())
+        }));
+        let finished_band = Signal::from(create_memo(move |_| {
+            let finished = finished.get()?;
+            let uncertainty = segments.with(|segments| {
+                drink.with_value(|drink| segment::uncertainty(segments, initial, drink, &model))
+            });
+            // Only the time in the current ambience is uncertain,
+            // earlier segments and a finish before them are over
+            let entered = segments.with(|segments| {
+                segments
+                    .last()
+                    .map_or(start, |segment| segment.timestamp_started)
+                    .min(finished)
+            });
+            Some(
+                ConfidenceBand::new(finished - entered, uncertainty.ok()?)
+                    .map(|time| entered + time),
+            )
+        }));
+        let current_time_left = Signal::derive(move || {
+            finished
+                .get()
+                .map(|finished| finished - current_time_signal.get())
+        });
+        let timer_finished = Signal::derive(move || {
+            current_time_left
+                .get()
+                .is_some_and(|time_left| time_left < Duration::zero())
+        });
+
+        let freezing = Signal::from(create_memo(move |_| {
+            segments.with(|segments| {
+                drink.with_value(|drink| {
+                    segment::time_freezing(segments, initial, drink, &model)
+                        .ok()
+                        .flatten()
+                })
+            })
+        }));
+        let frozen = Signal::from(create_memo(move |_| {
+            segments.with(|segments| {
+                drink.with_value(|drink| {
+                    segment::time_frozen(segments, initial, drink, &model)
+                        .ok()
+                        .flatten()
+                })
+            })
+        }));
+
+        // Looked up every second instead of predicting from the start
+        // of the timer, past its horizon the model has to run again
+        let course = Signal::from(create_memo(move |_| {
+            segments.with(|segments| {
+                drink.with_value(|drink| Course::new(segments, initial, drink, &model).ok())
+            })
+        }));
+        let current_phase = Signal::derive(move || {
+            let now = current_time_signal.get();
+            course
+                .with(|course| course.as_ref()?.phase_at(now))
+                .unwrap_or_else(|| {
+                    segments.with(|segments| {
+                        drink.with_value(|drink| {
+                            segment::phase_at(now, segments, initial, drink, &model)
+                                .unwrap_or(Phase::Liquid)
+                        })
+                    })
+                })
+        });
+        let current_temperature = Signal::derive(move || {
+            let now = current_time_signal.get();
+            current_batch()
+                .and_then(|prediction| prediction.temperature_at(now))
+                .or_else(|| course.with(|course| course.as_ref()?.temperature_at(now)))
+                .unwrap_or_else(|| {
+                    segments.with(|segments| {
+                        drink.with_value(|drink| {
+                            segment::temperature_at(now, segments, initial, drink, &model)
+                                .unwrap_or(initial)
+                        })
+                    })
+                })
+        });
+        let freezing_started = Signal::derive(move || current_phase.get() != Phase::Liquid);
+        let fully_frozen = Signal::derive(move || current_phase.get() == Phase::Frozen);
+
+        let current_ambience = Signal::derive(move || {
+            segments.with(|segments| {
+                segments
+                    .last()
+                    .expect("at least one segment")
+                    .ambience
+                    .clone()
+            })
+        });
+        let paused = Signal::derive(move || {
+            segments.with(|segments| segments.last().is_some_and(|segment| segment.paused))
+        });
+
+        let current_time_until_freezing = Signal::derive(move || {
+            freezing
+                .get()
+                .map(|freezing| freezing - current_time_signal.get())
+        });
+        // Ice in a drink that thaws in its current ambience is no
+        // reason to worry, only an ambience that freezes it is
+        let thawing = Signal::derive(move || {
+            let ambience = current_ambience.get();
+            drink.with_value(|drink| {
+                sink_temperature(drink, &ambience).as_kelvin() > drink.freezing_point.as_kelvin()
+            })
+        });
+        let current_state = Signal::derive(move || match current_phase.get() {
+            Phase::Frozen | Phase::Freezing(_) if thawing.get() => match timer_finished.get() {
+                false => TimerState::Running,
+                true => TimerState::Done,
+            },
+            Phase::Frozen => TimerState::Frozen,
+            Phase::Freezing(_) => TimerState::Freezing,
+            Phase::Liquid if !timer_finished.get() => TimerState::Running,
+            Phase::Liquid => match (finished.get(), freezing.get()) {
+                (Some(finished), Some(freezing)) => {
+                    let overdue = current_time_signal.get() - finished;
+                    let grace_period = freezing - finished;
+                    TimerState::Overdue(
+                        overdue.num_seconds() as f64 / grace_period.num_seconds().max(1) as f64,
+                    )
+                }
+                _ => TimerState::Done,
+            },
+        });
+
         TimerInfo {
-            id: Uuid::new_v4(),
+            id: snapshot.id,
             timestamp_started: start,
             timestamp_finished: finished,
-            drink: preset.drink.clone(),
-            initial_ambience: preset.initial_ambience.clone(),
-            ambient_ambience: preset.ambient_ambience.clone(),
-            target_ambience: preset.target_ambience,
+            finished_band,
+            timestamp_freezing: freezing,
+            timestamp_frozen: frozen,
+            drink: snapshot.drink,
+            initial_ambience: snapshot.initial_ambience,
+            ambient_ambience: snapshot.ambient_ambience,
+            target_ambience: snapshot.target_ambience,
+            segments,
+            course,
+            current_ambience,
+            paused,
             current_time_left,
-            current_temperature: Signal::derive(move || {
-                temperature_after_time(
-                    current_time_signal.get() - start,
-                    preset.initial_ambience.temperature,
-                    &preset.drink,
-                    &preset.ambient_ambience,
-                )
-            }),
+            current_temperature,
             timer_finished,
+            current_phase,
+            freezing_started,
+            fully_frozen,
+            current_time_until_freezing,
+            current_state,
+            alarm: create_rw_signal(snapshot.alarm),
+            measurements: create_rw_signal(snapshot.measurements),
+            batch,
+            error,
+            rotation_reminder: snapshot.rotation_reminder,
+            rotation_reminders_sent: create_rw_signal(snapshot.rotation_reminders_sent),
+            model,
+        }
+    }
+
+    /// Whether the drink warms up to its target instead of cooling down
+    pub fn is_warming(&self) -> bool {
+        is_warming(
+            self.initial_ambience.temperature,
+            self.target_ambience.temperature,
+        )
+    }
+
+    /// Moves the drink into another ambience from now on, with
+    /// the calibration of the drink there if there is one
+    pub fn move_to(
+        &self,
+        ambience: Ambience,
+        paused: bool,
+        now: DateTime<Local>,
+        calibrations: &[Calibration],
+    ) {
+        let calibration = self
+            .drink
+            .calibrated(&ambience, calibrations)
+            .calibrated_cooling_coefficient(&ambience);
+        self.segments.update(|segments| {
+            segments.push(Segment {
+                ambience,
+                timestamp_started: now,
+                paused,
+                calibration,
+            })
+        });
+    }
+
+    /// Moves the drink back into the last ambience before it was paused
+    pub fn resume(&self, now: DateTime<Local>, calibrations: &[Calibration]) {
+        let ambience = self.segments.with(|segments| {
+            segments
+                .iter()
+                .rev()
+                .find(|segment| !segment.paused)
+                .map(|segment| segment.ambience.clone())
+                .unwrap_or_else(|| self.ambient_ambience.clone())
+        });
+        self.move_to(ambience, false, now, calibrations);
+    }
+
+    pub fn snapshot(&self) -> TimerSnapshot {
+        TimerSnapshot {
+            id: self.id,
+            timestamp_started: self.timestamp_started,
+            drink: self.drink.clone(),
+            initial_ambience: self.initial_ambience.clone(),
+            ambient_ambience: self.ambient_ambience.clone(),
+            target_ambience: self.target_ambience.clone(),
+            alarm: self.alarm.get(),
+            measurements: self.measurements.get(),
+            segments: self.segments.get(),
+            rotation_reminder: self.rotation_reminder,
+            rotation_reminders_sent: self.rotation_reminders_sent.get(),
+            model: self.model,
         }
     }
 
+    /// Reminders to turn the bottle that should have been sent by
+    /// now, see [`segment::rotation_reminders_due`]
+    pub fn rotation_reminders_due(&self, now: DateTime<Local>) -> u32 {
+        self.segments.with(|segments| {
+            segment::rotation_reminders_due(
+                segments,
+                now,
+                &self.ambient_ambience,
+                self.rotation_reminder,
+            )
+        })
+    }
+
     // pub fn update(&self, current_time: DateTime<Local>) {
     //     self.current_time_left
     //         .update(|time| *time = self.timestamp_finished - current_time);
@@ -1,12 +1,50 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
 use super::{ambience::Ambience, drink::Drink};
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 // I always assume intial temp to be room temp and ambience to be freezer, so i could get rid of them
 pub struct TimerPreset {
+    pub id: Uuid,
     pub name: String,
     pub path_to_image: String,
     pub drink: Drink,
     pub initial_ambience: Ambience,
     pub ambient_ambience: Ambience,
     pub target_ambience: Ambience,
+    /// Minutes between reminders to turn the bottle, `None` for no reminders
+    #[serde(default)]
+    pub rotation_reminder: Option<u32>,
+}
+
+impl TimerPreset {
+    pub fn new(
+        name: &str,
+        path_to_image: &str,
+        drink: Drink,
+        initial_ambience: Ambience,
+        ambient_ambience: Ambience,
+        target_ambience: Ambience,
+    ) -> Self {
+        TimerPreset {
+            id: Uuid::new_v4(),
+            name: String::from(name),
+            path_to_image: String::from(path_to_image),
+            drink,
+            initial_ambience,
+            ambient_ambience,
+            target_ambience,
+            rotation_reminder: None,
+        }
+    }
+
+    /// Whether the drink warms up to its target in the ambience
+    /// instead of cooling down
+    pub fn is_warming(&self) -> bool {
+        super::is_warming(
+            self.initial_ambience.temperature,
+            self.target_ambience.temperature,
+        )
+    }
 }
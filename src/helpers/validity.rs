@@ -0,0 +1,219 @@
+//! How far the predictions can be trusted
+//!
+//! The exponential model treats the drink as one lump at a single
+//! temperature. That only holds while heat crosses the liquid much
+//! faster than it gets through the container, a Biot number below
+//! [`LUMPED_BIOT_LIMIT`]. Natural convection stirs the liquid, so the
+//! Biot number here uses the mixed conductivity of
+//! [`convection_factor`], not that of still liquid.
+//!
+//! Every predicted time comes with a [`ConfidenceBand`]: the cooling
+//! coefficient may be off by the relative [`Validity::uncertainty`]
+//! either way, which stretches or shortens the time by that factor.
+
+use chrono::Duration;
+
+use super::{
+    cooling_coefficient, duration_from_seconds,
+    evaporation::evaporation,
+    mixture::{convection_factor, Mixture},
+    outer_heat_transfer_coefficient, sink_temperature, Ambience, CoolingError, Drink, Temperature,
+};
+
+/// Biot number below which the drink can be treated as one lump
+pub const LUMPED_BIOT_LIMIT: f64 = 0.1;
+
+/// Relative uncertainty of the material properties and heat transfer
+/// coefficients, and what is left of it after a calibration
+const BASE_UNCERTAINTY: f64 = 0.15;
+const CALIBRATED_UNCERTAINTY: f64 = 0.05;
+/// Uncertainty per unit of the Biot number of a model that ignores
+/// the core lagging behind the wall, and of one that resolves it
+const LUMPED_BIOT_UNCERTAINTY: f64 = 0.5;
+const RESOLVED_BIOT_UNCERTAINTY: f64 = 0.1;
+/// Uncertainty added by the guessed humidity when water evaporates
+const EVAPORATION_UNCERTAINTY: f64 = 0.15;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Indicators of how well the models fit a drink in an ambience
+pub struct Validity {
+    /// Ratio of the resistance inside the liquid to the one outside
+    pub biot_number: f64,
+    /// Strength of the natural convection inside the drink at the start
+    pub rayleigh_number: f64,
+    /// The cooling coefficient was fitted to the user's measurements
+    pub calibrated: bool,
+    /// Water evaporates from the drink, depends on the humidity
+    pub evaporation: bool,
+}
+
+impl Validity {
+    /// Whether the drink may be treated as one lump at one temperature
+    pub fn is_lumped(&self) -> bool {
+        self.biot_number < LUMPED_BIOT_LIMIT
+    }
+
+    /// Relative uncertainty of the predicted times, e.g. 0.2 for
+    /// times that may be 20 % longer or shorter
+    ///
+    /// Models that resolve the gradient inside the drink suffer less
+    /// from a large Biot number.
+    pub fn uncertainty(&self, resolves_gradients: bool) -> f64 {
+        let base = match self.calibrated {
+            true => CALIBRATED_UNCERTAINTY,
+            false => BASE_UNCERTAINTY,
+        };
+        let biot = match resolves_gradients {
+            true => RESOLVED_BIOT_UNCERTAINTY,
+            false => LUMPED_BIOT_UNCERTAINTY,
+        };
+        let evaporation = match self.evaporation {
+            true => EVAPORATION_UNCERTAINTY,
+            false => 0.,
+        };
+
+        base + biot * self.biot_number.min(1.) + evaporation
+    }
+}
+
+/// Validity indicators of the drink starting at the initial
+/// temperature in the ambience
+pub fn validity(
+    initial_temperature: Temperature,
+    drink: &Drink,
+    ambience: &Ambience,
+) -> Result<Validity, CoolingError> {
+    cooling_coefficient(drink, ambience)?;
+
+    let mixture = Mixture::new(drink.alcohol_percentage).with_sugar(drink.sugar_content);
+    // Same cylinder as the finite-difference model
+    let radius = 2. * drink.container.volume / drink.container.surface_area;
+    let characteristic_length = drink.container.volume / drink.container.surface_area;
+    let outer_resistance = 1. / outer_heat_transfer_coefficient(drink, ambience)
+        + drink.container.get_thermal_resistance()
+        + 1. / mixture.heat_transfer_coefficient();
+    let temperature_difference =
+        (initial_temperature.as_kelvin() - sink_temperature(drink, ambience).as_kelvin()).abs();
+
+    // The convection is driven by the share of the temperature
+    // difference that falls across the liquid, which depends on the
+    // Biot number itself, a few rounds settle it
+    let mut biot_number = 0.;
+    let mut rayleigh_number = 0.;
+    for _ in 0..5 {
+        rayleigh_number = mixture.rayleigh_number(
+            radius,
+            temperature_difference * biot_number / (1. + biot_number),
+        );
+        let conductivity = mixture.thermal_conductivity()
+            * convection_factor(rayleigh_number, mixture.prandtl_number());
+        biot_number = characteristic_length / conductivity / outer_resistance;
+    }
+
+    Ok(Validity {
+        biot_number,
+        rayleigh_number,
+        calibrated: drink.calibrated_cooling_coefficient(ambience).is_some(),
+        evaporation: evaporation(drink, ambience).is_some(),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Range a prediction likely falls into, around the expected value
+pub struct ConfidenceBand<T> {
+    pub optimistic: T,
+    pub expected: T,
+    pub pessimistic: T,
+}
+
+impl ConfidenceBand<Duration> {
+    /// Band of a duration whose cooling coefficient may be off by
+    /// the relative uncertainty either way
+    pub fn new(expected: Duration, uncertainty: f64) -> Self {
+        let seconds = expected.num_milliseconds() as f64 / 1000.;
+        ConfidenceBand {
+            optimistic: duration_from_seconds(seconds / (1. + uncertainty)),
+            expected,
+            pessimistic: duration_from_seconds(seconds * (1. + uncertainty)),
+        }
+    }
+
+    /// Hours and minutes from the optimistic to the pessimistic end,
+    /// e.g. `0:47 – 1:02`
+    pub fn format(&self) -> String {
+        let format = |duration: Duration| {
+            let minutes = duration.num_minutes().max(0);
+            format!("{}:{:02}", minutes / 60, minutes % 60)
+        };
+        format!("{} – {}", format(self.optimistic), format(self.pessimistic))
+    }
+}
+
+impl<T> ConfidenceBand<T> {
+    pub fn map<U>(self, f: impl Fn(T) -> U) -> ConfidenceBand<U> {
+        ConfidenceBand {
+            optimistic: f(self.optimistic),
+            expected: f(self.expected),
+            pessimistic: f(self.pessimistic),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        catalog,
+        drink::{Layer, LayerMaterial},
+        fixtures::assert_close,
+    };
+
+    fn room() -> Temperature {
+        catalog::room_temperature().temperature
+    }
+
+    #[test]
+    fn band_stretches_the_time_by_the_uncertainty() {
+        let band = ConfidenceBand::new(Duration::minutes(60), 0.2);
+        assert_eq!(band.optimistic, Duration::minutes(50));
+        assert_eq!(band.pessimistic, Duration::minutes(72));
+        assert_eq!(band.format(), "0:50 – 1:12");
+    }
+
+    #[test]
+    fn drink_in_air_is_lumped() {
+        let validity = validity(room(), &catalog::beer_330_can(), &catalog::freezer()).unwrap();
+        assert!(validity.is_lumped(), "{}", validity.biot_number);
+        assert!(!validity.calibrated && !validity.evaporation);
+    }
+
+    #[test]
+    fn water_bath_raises_the_biot_number() {
+        let can = catalog::beer_330_can();
+        let air = validity(room(), &can, &catalog::fridge()).unwrap();
+        let bath = validity(room(), &can, &catalog::ice_bath()).unwrap();
+        assert!(bath.biot_number > air.biot_number);
+        assert!(bath.uncertainty(false) > bath.uncertainty(true));
+    }
+
+    #[test]
+    fn calibration_and_evaporation_change_the_uncertainty() {
+        let fridge = catalog::fridge();
+        let beer = catalog::beer_500_glass();
+        let plain = validity(room(), &beer, &fridge).unwrap();
+
+        let mut calibrated = beer.clone();
+        calibrated.calibration = Some((fridge.id, 1e-4));
+        let calibrated = validity(room(), &calibrated, &fridge).unwrap();
+        assert!(calibrated.calibrated);
+        assert_close(
+            plain.uncertainty(false) - calibrated.uncertainty(false),
+            BASE_UNCERTAINTY - CALIBRATED_UNCERTAINTY,
+            1e-9,
+        );
+
+        let wet = beer.with_layer(Layer::new(LayerMaterial::WetTowel));
+        let wet = validity(room(), &wet, &catalog::room_temperature()).unwrap();
+        assert!(wet.evaporation);
+    }
+}
@@ -1,9 +1,14 @@
+#[cfg(feature = "web")]
 mod app;
+#[cfg(feature = "web")]
 mod components;
 pub mod helpers;
+#[cfg(feature = "web")]
 mod pages;
 
-pub use self::{app::App, calculate_surface_area::*, helpers::*, pages::*};
+#[cfg(feature = "web")]
+pub use self::{app::App, pages::*};
+pub use self::{calculate_surface_area::*, helpers::*};
 
 pub mod calculate_surface_area {
     use std::f64::consts::PI;
@@ -1,6 +1,9 @@
 use crate::{
-    app::CurrentlyRunningTimers,
-    components::{PresetSummary, TimerPresetButton, TimerTile},
+    app::{CurrentlyRunningTimers, CustomTimerPresets},
+    components::{
+        AlarmBanner, DrinkEditor, ModelSwitch, Planner, PresetBuilder, PresetSummary,
+        TimerPresetButton, TimerTile, UnitSwitch,
+    },
     TimerPreset,
 };
 use leptos::*;
@@ -8,11 +11,13 @@ use leptos_icons::{Icon, OcIcon::OcXSm};
 
 #[component]
 pub fn Home(
-    timer_presets: Vec<TimerPreset>,
+    #[prop(into)] timer_presets: Signal<Vec<TimerPreset>>,
     selected_preset_signal: RwSignal<TimerPreset>,
 ) -> impl IntoView {
     let currently_running_timers = expect_context::<CurrentlyRunningTimers>().0;
+    let custom_timer_presets = expect_context::<CustomTimerPresets>().0;
     let modal_showing_signal = create_rw_signal(false);
+    let editing_preset_signal = create_rw_signal::<Option<TimerPreset>>(None);
 
     // let modal = create_node_ref::<Dialog>();
     // // modal.get().expect("to have modal").show();
@@ -30,14 +35,19 @@ pub fn Home(
         <div class="main_content_container">
             <header>
                 <h1>"Bier Timer"</h1>
+                <div class="switches">
+                    <ModelSwitch/>
+                    <UnitSwitch/>
+                </div>
             </header>
             <main>
+                <AlarmBanner/>
                 <section>
                     <h3>"Laufende Timer"</h3>
                     <div class="running_timers_wrapper">
 
                         {move || {
-                            if currently_running_timers.get().len() == 0 {
+                            if currently_running_timers.get().is_empty() {
                                 view! { <p>"Erstelle unten einen neuen Timer"</p> }.into_view()
                             } else {
                                 currently_running_timers
@@ -58,22 +68,84 @@ pub fn Home(
 
                     <div class="timer_presets_wrapper">
 
-                        {timer_presets
-                            .iter()
-                            .map(|preset| {
-                                view! {
-                                    <TimerPresetButton
-                                        preset=preset.clone()
-                                        modal_showing_signal
-                                        selected_preset_signal
-                                    />
-                                }
-                            })
-                            .collect_view()}
+                        {move || {
+                            timer_presets
+                                .get()
+                                .into_iter()
+                                .map(|preset| {
+                                    view! {
+                                        <TimerPresetButton
+                                            preset
+                                            modal_showing_signal
+                                            selected_preset_signal
+                                        />
+                                    }
+                                })
+                                .collect_view()
+                        }}
+
+                        {move || {
+                            custom_timer_presets
+                                .get()
+                                .into_iter()
+                                .map(|preset| {
+                                    let preset_id = preset.id;
+                                    let editing_preset = preset.clone();
+                                    view! {
+                                        <div class="custom_timer_preset">
+                                            <TimerPresetButton
+                                                preset
+                                                modal_showing_signal
+                                                selected_preset_signal
+                                            />
+                                            <div class="custom_timer_preset_controls">
+                                                <button
+                                                    class="button"
+                                                    on:click=move |_| {
+                                                        editing_preset_signal
+                                                            .set(Some(editing_preset.clone()))
+                                                    }
+                                                >
+
+                                                    "Bearbeiten"
+                                                </button>
+                                                <button
+                                                    class="button danger"
+                                                    on:click=move |_| {
+                                                        custom_timer_presets
+                                                            .update(|presets| {
+                                                                presets.retain(|p| p.id != preset_id)
+                                                            });
+                                                    }
+                                                >
+
+                                                    "Löschen"
+                                                </button>
+                                            </div>
+                                        </div>
+                                    }
+                                })
+                                .collect_view()
+                        }}
 
                     </div>
                 </section>
 
+                <section>
+                    <h3>"Vorausplanen"</h3>
+                    <Planner timer_presets/>
+                </section>
+
+                <section>
+                    <h3>"Eigenen Timer erstellen"</h3>
+                    <PresetBuilder editing_preset_signal/>
+                </section>
+
+                <section>
+                    <h3>"Eigenes Getränk erstellen"</h3>
+                    <DrinkEditor/>
+                </section>
+
             // <section>
             // <h3>"Weiterführende Informationen"</h3>
             // </section>